@@ -0,0 +1,180 @@
+use crate::model::Model;
+use crate::{VoxError, Voxel};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+// binvox stores occupancy only with y as the up axis. the z axis of the model is written as the
+// binvox y axis so models are upright, which makes the data go over x, then y, then z of the model.
+
+impl Model {
+    /// Writes the model to a .binvox file. Only which positions have a voxel is saved, colors are lost.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 1).unwrap();
+    ///
+    /// let path = std::env::temp_dir().join("cube.binvox");
+    /// vox.models[0].export_binvox(&path).unwrap();
+    /// ```
+    pub fn export_binvox(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let size = (
+            self.size.0 as usize,
+            self.size.1 as usize,
+            self.size.2 as usize,
+        );
+        let mut occupied = vec![false; size.0 * size.1 * size.2];
+        for voxel in self.voxels.iter() {
+            let (x, y, z) = voxel.position;
+            if (x as usize) < size.0 && (y as usize) < size.1 && (z as usize) < size.2 {
+                occupied[x as usize * size.1 * size.2 + y as usize * size.2 + z as usize] = true;
+            }
+        }
+
+        let translation = self.position.unwrap_or((0, 0, 0));
+        let scale = size.0.max(size.1).max(size.2);
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(
+            writer,
+            "#binvox 1\ndim {} {} {}\ntranslate {} {} {}\nscale {}\ndata\n",
+            size.0, size.1, size.2, translation.0, translation.1, translation.2, scale
+        )?;
+
+        //(value, count) pairs where a run can be up to 255 long
+        let mut data = Vec::new();
+        let mut cells = occupied.iter();
+        if let Some(&first) = cells.next() {
+            let mut value = first;
+            let mut count: u8 = 1;
+            for &cell in cells {
+                if cell == value && count < 255 {
+                    count += 1;
+                } else {
+                    data.push(value as u8);
+                    data.push(count);
+                    value = cell;
+                    count = 1;
+                }
+            }
+            data.push(value as u8);
+            data.push(count);
+        }
+        writer.write_all(&data)?;
+        writer.flush()
+    }
+
+    /// Reads a .binvox file into a new model where every voxel has the color index given.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 1).unwrap();
+    /// let path = std::env::temp_dir().join("import.binvox");
+    /// vox.models[0].export_binvox(&path).unwrap();
+    ///
+    /// let model = Model::from_binvox(&path, 3).unwrap();
+    /// assert_eq!(125, model.num_of_voxels());
+    /// ```
+    pub fn from_binvox(path: impl AsRef<Path>, color: u8) -> Result<Model, VoxError> {
+        if color == 0 {
+            return Err(VoxError::InvalidColorIndex(color));
+        }
+        let mut contents = Vec::new();
+        File::open(path)?.read_to_end(&mut contents)?;
+        if !contents.starts_with(b"#binvox") {
+            return Err(VoxError::InvalidFile(String::from("missing #binvox line")));
+        }
+
+        let mut size = None;
+        let mut translation = (0, 0, 0);
+        let mut cursor = 0;
+        loop {
+            let line_end = contents[cursor..]
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .ok_or_else(|| VoxError::InvalidFile(String::from("binvox header has no data")))?;
+            let line = String::from_utf8_lossy(&contents[cursor..cursor + line_end]).to_string();
+            cursor += line_end + 1;
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.first() {
+                Some(&"#binvox") | Some(&"scale") | None => {}
+                Some(&"dim") => size = Some(parse_binvox_dim(&words)?),
+                Some(&"translate") => translation = parse_binvox_translate(&words)?,
+                Some(&"data") => break,
+                Some(word) => {
+                    return Err(VoxError::InvalidFile(format!(
+                        "unknown binvox header line {}",
+                        word
+                    )))
+                }
+            }
+        }
+        let size =
+            size.ok_or_else(|| VoxError::InvalidFile(String::from("binvox has no dim line")))?;
+
+        let mut model = Model::new(size.0, size.1, size.2);
+        let (size_y, size_z) = (size.1 as usize, size.2 as usize);
+        let num_of_cells = size.0 as usize * size_y * size_z;
+        let mut index = 0;
+        for pair in contents[cursor..].chunks(2) {
+            if pair.len() != 2 {
+                return Err(VoxError::InvalidFile(String::from(
+                    "binvox data is cut off",
+                )));
+            }
+            let count = pair[1] as usize;
+            if index + count > num_of_cells {
+                return Err(VoxError::InvalidFile(String::from(
+                    "binvox data is larger than its size",
+                )));
+            }
+            if pair[0] != 0 {
+                for cell in index..index + count {
+                    model.voxels.push(Voxel::new(
+                        (cell / (size_y * size_z)) as u8,
+                        ((cell / size_z) % size_y) as u8,
+                        (cell % size_z) as u8,
+                        color,
+                    ));
+                }
+            }
+            index += count;
+        }
+
+        if translation != (0, 0, 0) {
+            model.position = Some(translation);
+        }
+        Ok(model)
+    }
+}
+
+fn parse_binvox_dim(words: &[&str]) -> Result<(u16, u16, u16), VoxError> {
+    let mut size = [0u16; 3];
+    for (axis, value) in size.iter_mut().enumerate() {
+        *value = words
+            .get(axis + 1)
+            .and_then(|word| word.parse().ok())
+            .filter(|value| *value <= 256)
+            .ok_or_else(|| VoxError::InvalidFile(String::from("binvox dim is not valid")))?;
+    }
+    Ok((size[0], size[1], size[2]))
+}
+
+fn parse_binvox_translate(words: &[&str]) -> Result<(i32, i32, i32), VoxError> {
+    let mut translation = [0i32; 3];
+    for (axis, value) in translation.iter_mut().enumerate() {
+        *value = words
+            .get(axis + 1)
+            .and_then(|word| word.parse::<f32>().ok())
+            .map(|value| value.round() as i32)
+            .ok_or_else(|| VoxError::InvalidFile(String::from("binvox translate is not valid")))?;
+    }
+    Ok((translation[0], translation[1], translation[2]))
+}
@@ -1,3 +1,4 @@
+use crate::VoxError;
 use std::ops::Add;
 
 /// Color containing 4 bytes for red, green, blue, and alpha.
@@ -21,6 +22,182 @@ impl Color {
     pub fn new(r: u8, g: u8, b: u8, a: u8) -> Color {
         Color { r, g, b, a }
     }
+
+    /// Makes a color from hex digits written as RRGGBB or RRGGBBAA, with or without a # in front. Alpha is
+    /// 255 if it is not given. It will return an error if there is not 6 or 8 hex digits.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Color;
+    ///
+    /// assert_eq!(Color::new(255, 136, 0, 255), Color::from_hex("#ff8800").unwrap());
+    /// assert_eq!(Color::new(255, 136, 0, 128), Color::from_hex("FF880080").unwrap());
+    /// assert!(Color::from_hex("#ff88").is_err());
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Color, VoxError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let invalid = || VoxError::InvalidHexColor(String::from(hex));
+        if (digits.len() != 6 && digits.len() != 8)
+            || !digits.chars().all(|digit| digit.is_ascii_hexdigit())
+        {
+            return Err(invalid());
+        }
+
+        let byte = |at: usize| u8::from_str_radix(&digits[at..at + 2], 16).map_err(|_| invalid());
+        let a = if digits.len() == 8 { byte(6)? } else { 255 };
+        Ok(Color::new(byte(0)?, byte(2)?, byte(4)?, a))
+    }
+
+    /// Writes the color as 8 lowercase hex digits in RRGGBBAA order with a # in front, which from_hex() can
+    /// read.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Color;
+    ///
+    /// assert_eq!("#ff8800ff", Color::new(255, 136, 0, 255).to_hex());
+    /// ```
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    /// Gets the palette MagicaVoxel uses when a file does not have one, in the same order as the palette of
+    /// a VoxFile, so the color for index 1 is first. It is used by VoxFile::new().
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Color;
+    ///
+    /// let palette = Color::default_palette();
+    /// //index 1 is white
+    /// assert_eq!(Color::new(255, 255, 255, 255), palette[0]);
+    /// ```
+    pub fn default_palette() -> [Color; 256] {
+        let mut palette = [Color::new(0, 0, 0, 0); 256];
+        //every mix of 6 levels of red, green, and blue except black, then ramps of red, green, blue, and grey
+        let levels = [255, 204, 153, 102, 51, 0];
+        let ramp = [238, 221, 187, 170, 136, 119, 85, 68, 34, 17];
+        let mut colors = Vec::with_capacity(255);
+        for &r in levels.iter() {
+            for &g in levels.iter() {
+                for &b in levels.iter() {
+                    colors.push(Color::new(r, g, b, 255));
+                }
+            }
+        }
+        colors.pop();
+        colors.extend(ramp.iter().map(|&r| Color::new(r, 0, 0, 255)));
+        colors.extend(ramp.iter().map(|&g| Color::new(0, g, 0, 255)));
+        colors.extend(ramp.iter().map(|&b| Color::new(0, 0, b, 255)));
+        colors.extend(ramp.iter().map(|&grey| Color::new(grey, grey, grey, 255)));
+
+        //the last color is for index 0, which is empty
+        palette[..255].copy_from_slice(&colors);
+        palette
+    }
+
+    /// Gets the index of the color in the palette that is closest to this color using the metric. Alpha is
+    /// not used when comparing colors, and the first closest color is used if there is a tie.
+    ///
+    /// # Panics
+    /// Panics if the palette is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, ColorMetric};
+    ///
+    /// let palette = [
+    ///     Color::new(0, 0, 0, 255),
+    ///     Color::new(255, 0, 0, 255),
+    ///     Color::new(0, 0, 255, 255),
+    /// ];
+    ///
+    /// let dark_red = Color::new(150, 20, 10, 255);
+    /// assert_eq!(1, dark_red.nearest_in(&palette, ColorMetric::Euclidean));
+    /// assert_eq!(1, dark_red.nearest_in(&palette, ColorMetric::Weighted));
+    /// ```
+    pub fn nearest_in(&self, palette: &[Color], metric: ColorMetric) -> usize {
+        assert!(!palette.is_empty(), "palette can not be empty");
+
+        let mut nearest = 0;
+        let mut nearest_distance = u64::MAX;
+        for (index, color) in palette.iter().enumerate() {
+            let distance = metric.distance(self, color);
+            if distance < nearest_distance {
+                nearest = index;
+                nearest_distance = distance;
+            }
+        }
+        nearest
+    }
+}
+
+/// How the distance between two colors is measured.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorMetric {
+    /// Straight line distance between the red, green, and blue values.
+    Euclidean,
+    /// Red, green, and blue are weighted depending on how red the colors are, which is closer to how
+    /// different the colors look.
+    Weighted,
+}
+
+impl ColorMetric {
+    //squared distance, only used for comparing
+    fn distance(self, first: &Color, second: &Color) -> u64 {
+        let r = first.r as i64 - second.r as i64;
+        let g = first.g as i64 - second.g as i64;
+        let b = first.b as i64 - second.b as i64;
+
+        let distance = match self {
+            ColorMetric::Euclidean => r * r + g * g + b * b,
+            ColorMetric::Weighted => {
+                let red_mean = (first.r as i64 + second.r as i64) / 2;
+                (((512 + red_mean) * r * r) >> 8) + 4 * g * g + (((767 - red_mean) * b * b) >> 8)
+            }
+        };
+        distance as u64
+    }
+}
+
+//hue from 0 to 360, and saturation and value from 0 to 1
+pub(crate) fn to_hsv(color: &Color) -> (f32, f32, f32) {
+    let r = color.r as f32 / 255.0;
+    let g = color.g as f32 / 255.0;
+    let b = color.b as f32 / 255.0;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let range = max - min;
+
+    let hue = if range == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / range).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / range + 2.0)
+    } else {
+        60.0 * ((r - g) / range + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { range / max };
+    (hue, saturation, max)
+}
+
+//alpha is not part of hsv so it is given separately
+pub(crate) fn from_hsv(hue: f32, saturation: f32, value: f32, a: u8) -> Color {
+    let hue = hue.rem_euclid(360.0);
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let min = value - chroma;
+    let byte = |channel: f32| ((channel + min) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Color::new(byte(r), byte(g), byte(b), a)
 }
 
 impl PartialEq for Color {
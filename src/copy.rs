@@ -1,6 +1,7 @@
-use crate::node::Transform;
-use crate::node::{Node, NodeAttributes, NodeType};
+use crate::node::{Frame, Transform};
+use crate::node::{Node, NodeAttributes, NodeType, ShapeModel};
 
+#[derive(Clone)]
 pub struct ModelCopy {
     pub model_id: i32,
     pub position: Option<(i32, i32, i32)>,
@@ -14,7 +15,10 @@ impl ModelCopy {
         let mut attributes = NodeAttributes::new();
         attributes.name = self.name.clone();
         let mut transform_node = Node::new(NodeType::Transform(self.transform_data()), attributes);
-        let shape_node = Node::new(NodeType::Shape(self.model_id), NodeAttributes::new());
+        let shape_node = Node::new(
+            NodeType::Shape(vec![ShapeModel::new(self.model_id)]),
+            NodeAttributes::new(),
+        );
         transform_node.add_child(shape_node);
 
         transform_node
@@ -23,11 +27,14 @@ impl ModelCopy {
     pub(crate) fn transform_data(&self) -> Transform {
         Transform {
             layer: self.layer.unwrap_or_else(|| 0),
-            rotation: match self.rotation {
-                None => None,
-                Some(rot) => Some(rot as i32),
-            },
-            translation: self.position,
+            frames: vec![Frame {
+                rotation: match self.rotation {
+                    None => None,
+                    Some(rot) => Some(rot as i32),
+                },
+                translation: self.position,
+                index: None,
+            }],
         }
     }
 }
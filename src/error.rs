@@ -0,0 +1,125 @@
+use crate::Axis;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Errors that can happen when editing models or files.
+#[derive(Debug)]
+pub enum VoxError {
+    /// A voxel position is outside of the size of the model.
+    VoxelOutOfBounds {
+        pos: (u8, u8, u8),
+        size: (u16, u16, u16),
+    },
+    /// A model would be larger than 256 on an axis.
+    SizeTooLarge { axis: Axis, value: u32 },
+    /// A range has its start after its end.
+    InvalidRange { start: u8, end: u8 },
+    /// Color index 0 is used, which means there is no voxel, or a range of color indexes would go past 255.
+    InvalidColorIndex(u8),
+    /// A voxel is added where there already is one in a model that is strict.
+    DuplicatePosition((u8, u8, u8)),
+    /// Every index on the palette is used by a different color.
+    PaletteFull,
+    /// There is no model at an index of the models in a file.
+    InvalidModelIndex(usize),
+    /// Two axes that need to be different are the same.
+    SameAxis(Axis),
+    /// A model or copy is rotated where only models that are not rotated can be used.
+    RotatedModel(u8),
+    /// A color is not written as 6 or 8 hex digits.
+    InvalidHexColor(String),
+    /// A file could not be read or written.
+    Io(io::Error),
+    /// A file could not be parsed.
+    InvalidFile(String),
+    /// The data does not start with the "VOX " header, so it is not a .vox file.
+    NotAVoxFile,
+    /// Something is too large in bytes to be written in a .vox file.
+    TooLarge(u64),
+}
+
+impl fmt::Display for VoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoxError::VoxelOutOfBounds { pos, size } => write!(
+                f,
+                "voxel position {:?} is outside of the model size {:?}",
+                pos, size
+            ),
+            VoxError::SizeTooLarge { axis, value } => write!(
+                f,
+                "size {} on the {:?} axis is greater than 256",
+                value, axis
+            ),
+            VoxError::InvalidRange { start, end } => {
+                write!(f, "range start {} is after the end {}", start, end)
+            }
+            VoxError::InvalidColorIndex(index) => {
+                write!(f, "color index {} needs to be between 1 and 255", index)
+            }
+            VoxError::DuplicatePosition(pos) => {
+                write!(f, "there already is a voxel at position {:?}", pos)
+            }
+            VoxError::PaletteFull => write!(f, "every index on the palette is used"),
+            VoxError::InvalidModelIndex(index) => write!(f, "there is no model at index {}", index),
+            VoxError::SameAxis(axis) => {
+                write!(f, "both axes are {:?}, they need to be different", axis)
+            }
+            VoxError::RotatedModel(rotation) => {
+                write!(
+                    f,
+                    "rotation {} is not supported, the model needs to not be rotated",
+                    rotation
+                )
+            }
+            VoxError::InvalidHexColor(hex) => write!(
+                f,
+                "{:?} is not a color written as RRGGBB or RRGGBBAA in hex",
+                hex
+            ),
+            VoxError::Io(error) => write!(f, "{}", error),
+            VoxError::InvalidFile(reason) => write!(f, "invalid file: {}", reason),
+            VoxError::NotAVoxFile => write!(f, "not a .vox file, the VOX header is missing"),
+            VoxError::TooLarge(size) => write!(
+                f,
+                "{} bytes is larger than a .vox chunk can hold ({} bytes)",
+                size,
+                u32::MAX
+            ),
+        }
+    }
+}
+
+/// Problems found when reading a file that do not stop it from being loaded.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Warning {
+    /// The file has a chunk with a name that is not known, which is skipped.
+    UnknownChunk(String),
+    /// The version in the file header is not one that has been tested.
+    UnexpectedVersion(u32),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::UnknownChunk(name) => write!(f, "unknown chunk {:?} was skipped", name),
+            Warning::UnexpectedVersion(version) => write!(f, "unexpected file version {}", version),
+        }
+    }
+}
+
+impl Error for VoxError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            VoxError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for VoxError {
+    fn from(error: io::Error) -> VoxError {
+        VoxError::Io(error)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::Voxel;
+use std::collections::HashMap;
+
+/// A view of a model where the color of a voxel can be looked up by its position. Only the positions that
+/// have a voxel are stored, so it uses memory for each voxel instead of for each position in the model.
+/// Like an OccupancyMap it is not updated when the model changes, and if there is more than one voxel at a
+/// position the last one is used.
+///
+/// # Example
+/// ```
+/// use create_vox::VoxFile;
+///
+/// let mut vox = VoxFile::new(10, 10, 10);
+/// vox.models[0].add_voxel_at_pos(1, 2, 3, 5).unwrap();
+///
+/// let grid = vox.models[0].grid();
+/// assert_eq!(Some(5), grid.get(1, 2, 3));
+/// assert_eq!(None, grid.get(3, 2, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct VoxelGrid {
+    size: (u16, u16, u16),
+    //position to color index
+    colors: HashMap<(u8, u8, u8), u8>,
+}
+
+impl VoxelGrid {
+    pub(crate) fn new(size: (u16, u16, u16), voxels: &[Voxel]) -> VoxelGrid {
+        let mut colors = HashMap::with_capacity(voxels.len());
+        for voxel in voxels.iter() {
+            colors.insert(voxel.position, voxel.color_index);
+        }
+
+        VoxelGrid { size, colors }
+    }
+
+    /// Gets the color index of the voxel at the position, or None if there is no voxel there.
+    pub fn get(&self, x: u8, y: u8, z: u8) -> Option<u8> {
+        self.colors.get(&(x, y, z)).copied()
+    }
+
+    /// Gets the size of the model the grid was made from.
+    pub fn size(&self) -> (u16, u16, u16) {
+        self.size
+    }
+}
@@ -1,8 +1,11 @@
 use crate::node::bool_to_string;
 use crate::riff::{Dict, VoxString, LAYR};
+use crate::VoxError;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{self, BufWriter};
 
+/// A layer that models can be put on. The layer of a model is the id of the layer.
+#[derive(Clone, Debug)]
 pub struct Layer {
     id: i32,
     pub name: Option<String>,
@@ -10,6 +13,16 @@ pub struct Layer {
 }
 
 impl Layer {
+    /// Creates a new layer. VoxFile::add_layer() should be used to add a layer to a file so it gets an id
+    /// that is not used.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Layer;
+    ///
+    /// let layer = Layer::new(String::from("trees"), false, 3);
+    /// assert_eq!(3, layer.id());
+    /// ```
     pub fn new(name: String, hidden: bool, id: i32) -> Layer {
         Layer {
             id,
@@ -17,34 +30,48 @@ impl Layer {
             hidden: Some(hidden),
         }
     }
-    pub fn from_chunk(chunk: LAYR) -> Layer {
-        Layer {
+
+    /// Gets the id of the layer, which is used by Model::layer.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let id = vox.add_layer(String::from("my layer"), false);
+    /// assert_eq!(id, vox.layers[0].id());
+    /// ```
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub(crate) fn from_chunk(chunk: LAYR) -> Result<Layer, VoxError> {
+        Ok(Layer {
             id: chunk.layer_id,
             name: Layer::has_name(&chunk),
-            hidden: Layer::is_hidden(&chunk),
-        }
+            hidden: Layer::is_hidden(&chunk)?,
+        })
     }
 
-    pub(crate) fn is_hidden(chunk: &LAYR) -> Option<bool> {
+    pub(crate) fn is_hidden(chunk: &LAYR) -> Result<Option<bool>, VoxError> {
         if !chunk.layer_attributes.pairs.is_empty() {
             for pair in chunk.layer_attributes.pairs.iter() {
                 if pair.0.content == *"_hidden" {
-                    println!("here");
-                    return Some(
-                        pair.1
-                            .content
-                            .parse::<i32>()
-                            .expect("failed to parse value")
-                            == 1,
-                    );
+                    let value = pair.1.content.parse::<i32>().map_err(|_| {
+                        VoxError::InvalidFile(format!(
+                            "hidden value {:?} of layer {} is not a number",
+                            pair.1.content, chunk.layer_id
+                        ))
+                    })?;
+                    return Ok(Some(value == 1));
                 }
             }
         }
 
-        None
+        Ok(None)
     }
 
-    pub fn has_name(chunk: &LAYR) -> Option<String> {
+    pub(crate) fn has_name(chunk: &LAYR) -> Option<String> {
         if !chunk.layer_attributes.pairs.is_empty() {
             for pair in chunk.layer_attributes.pairs.iter() {
                 if pair.0.content == *"_name" {
@@ -56,7 +83,7 @@ impl Layer {
         None
     }
 
-    pub fn to_chunk(&self) -> LAYR {
+    pub(crate) fn to_chunk(&self) -> LAYR {
         LAYR {
             layer_id: self.id,
             layer_attributes: self.to_dict(),
@@ -64,7 +91,7 @@ impl Layer {
         }
     }
 
-    pub fn to_dict(&self) -> Dict {
+    pub(crate) fn to_dict(&self) -> Dict {
         let num_pairs: i32;
         if self.hidden.is_some() && self.name.is_some() {
             num_pairs = 2;
@@ -98,11 +125,11 @@ impl Layer {
         }
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        self.to_chunk().write(buf_writer);
+    pub(crate) fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
+        self.to_chunk().write(buf_writer)
     }
 
-    pub fn get_size(&self) -> i32 {
+    pub(crate) fn get_size(&self) -> i32 {
         self.to_chunk().get_size()
     }
 }
@@ -1,18 +1,34 @@
+mod axis;
+mod binvox;
 mod color;
 mod convert;
 mod copy;
+mod error;
+mod grid;
 mod layer;
+mod material;
 mod model;
 #[allow(dead_code)]
 mod node;
+mod obj;
+mod occupancy;
 #[allow(dead_code)]
 mod riff;
+mod source;
 mod voxel;
 mod voxfile;
+mod vxm;
 mod writing;
 
+pub use axis::Axis;
 pub use color::*;
+pub use error::{VoxError, Warning};
+pub use grid::VoxelGrid;
+pub use layer::Layer;
+pub use material::{Material, MaterialType};
 pub use model::Model;
-use std::io::Write;
+pub use occupancy::OccupancyMap;
+pub use riff::Rotation;
+pub use source::VoxelSource;
 pub use voxel::*;
-pub use voxfile::VoxFile;
+pub use voxfile::{PalettePolicy, VoxFile};
@@ -0,0 +1,153 @@
+use crate::riff::{Dict, VoxString, MATL};
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+/// How a material is rendered in MagicaVoxel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaterialType {
+    Diffuse,
+    Metal,
+    Glass,
+    Emit,
+    Blend,
+    Media,
+    Cloud,
+    /// A type that is not known, with the name it has in the file.
+    Other(String),
+}
+
+impl MaterialType {
+    fn from_name(name: &str) -> MaterialType {
+        match name {
+            "_diffuse" => MaterialType::Diffuse,
+            "_metal" => MaterialType::Metal,
+            "_glass" => MaterialType::Glass,
+            "_emit" => MaterialType::Emit,
+            "_blend" => MaterialType::Blend,
+            "_media" => MaterialType::Media,
+            "_cloud" => MaterialType::Cloud,
+            other => MaterialType::Other(String::from(other)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            MaterialType::Diffuse => "_diffuse",
+            MaterialType::Metal => "_metal",
+            MaterialType::Glass => "_glass",
+            MaterialType::Emit => "_emit",
+            MaterialType::Blend => "_blend",
+            MaterialType::Media => "_media",
+            MaterialType::Cloud => "_cloud",
+            MaterialType::Other(name) => name,
+        }
+    }
+}
+
+/// Material of a color index on the palette. The properties MagicaVoxel uses the most have their own fields,
+/// and the rest are kept as they are in the file so they are saved again.
+///
+/// # Example
+/// ```
+/// use create_vox::{Material, MaterialType, VoxFile};
+///
+/// let mut vox = VoxFile::new(10, 10, 10);
+/// let mut glow = Material::new(1);
+/// glow.material_type = Some(MaterialType::Emit);
+/// glow.emit = Some(0.5);
+/// glow.flux = Some(2.0);
+/// vox.materials.push(glow);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    /// The color index the material is for.
+    pub id: i32,
+    pub material_type: Option<MaterialType>,
+    pub metal: Option<f32>,
+    pub rough: Option<f32>,
+    pub emit: Option<f32>,
+    pub flux: Option<f32>,
+    /// Other properties as (key, value), such as ("_ior", "0.3").
+    pub other_properties: Vec<(String, String)>,
+}
+
+impl Material {
+    /// Creates a material for the color index with no properties set.
+    pub fn new(id: i32) -> Material {
+        Material {
+            id,
+            material_type: None,
+            metal: None,
+            rough: None,
+            emit: None,
+            flux: None,
+            other_properties: vec![],
+        }
+    }
+
+    pub(crate) fn from_chunk(chunk: MATL) -> Material {
+        let mut material = Material::new(chunk.material_id);
+        for (key, value) in chunk.properties.pairs {
+            let number = value.content.parse::<f32>().ok();
+            match (key.content.as_str(), number) {
+                ("_type", _) => {
+                    material.material_type = Some(MaterialType::from_name(&value.content))
+                }
+                ("_metal", Some(number)) => material.metal = Some(number),
+                ("_rough", Some(number)) => material.rough = Some(number),
+                ("_emit", Some(number)) => material.emit = Some(number),
+                ("_flux", Some(number)) => material.flux = Some(number),
+                //values that are not numbers are kept as they are
+                _ => material.other_properties.push((key.content, value.content)),
+            }
+        }
+
+        material
+    }
+
+    pub(crate) fn to_chunk(&self) -> MATL {
+        let mut pairs = Vec::new();
+        if let Some(material_type) = &self.material_type {
+            pairs.push(("_type", String::from(material_type.name())));
+        }
+        let numbers = [
+            ("_metal", self.metal),
+            ("_rough", self.rough),
+            ("_emit", self.emit),
+            ("_flux", self.flux),
+        ];
+        for (key, number) in numbers.iter() {
+            if let Some(number) = number {
+                pairs.push((key, number.to_string()));
+            }
+        }
+
+        let mut pairs: Vec<(VoxString, VoxString)> = pairs
+            .into_iter()
+            .map(|(key, value)| (vox_string(key), vox_string(&value)))
+            .collect();
+        for (key, value) in self.other_properties.iter() {
+            pairs.push((vox_string(key), vox_string(value)));
+        }
+
+        MATL {
+            material_id: self.id,
+            properties: Dict {
+                num_of_pairs: pairs.len() as i32,
+                pairs,
+            },
+        }
+    }
+
+    pub(crate) fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
+        self.to_chunk().write(buf_writer)
+    }
+
+    pub(crate) fn get_size(&self) -> i32 {
+        self.to_chunk().get_size()
+    }
+}
+
+fn vox_string(content: &str) -> VoxString {
+    VoxString::new(content.len() as i32, String::from(content))
+}
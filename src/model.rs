@@ -1,21 +1,61 @@
 use crate::convert::*;
-use crate::node::{Node, NodeAttributes, NodeType, Transform};
-use crate::riff::write_chunk;
+use crate::node::{Frame, Node, NodeAttributes, NodeType, ShapeModel, Transform};
+use crate::riff::{write_chunk, CHUNK_HEADER_SIZE};
 use crate::writing::*;
 use crate::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{self, BufWriter};
 
 /// Holds voxel data
 #[derive(Clone)]
 pub struct Model {
     pub size: (u16, u16, u16),
+    /// The voxels of the model. iter() and iter_mut() are the preferred way to go through them, since how
+    /// the voxels are stored may change.
     pub voxels: Vec<Voxel>,
     pub position: Option<(i32, i32, i32)>,
     pub rotation: Option<u8>,
     pub layer: Option<i32>,
     pub name: Option<String>,
     pub(crate) id: i32,
+    //models shown for each keyframe, empty when the model is not animated
+    pub(crate) frames: Vec<ShapeModel>,
+    //only made when build_index() is called
+    voxel_index: Option<OccupancyMap>,
+    //add_voxel() returns an error for a position that already has a voxel
+    strict: bool,
+}
+
+//the smallest and largest corner of a box
+type Corners = ((u8, u8, u8), (u8, u8, u8));
+
+//more voxels than this in one model is slow to use in MagicaVoxel
+const OVERSIZED_VOXELS: usize = 1 << 22;
+
+//shows the number of voxels instead of all of them
+impl fmt::Debug for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Model")
+            .field("size", &self.size)
+            .field("num_of_voxels", &self.voxels.len())
+            .field("position", &self.position)
+            .field("rotation", &self.rotation)
+            .field("layer", &self.layer)
+            .field("name", &self.name)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a Model {
+    type Item = &'a Voxel;
+    type IntoIter = std::slice::Iter<'a, Voxel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.voxels.iter()
+    }
 }
 
 #[allow(unused_variables)]
@@ -41,10 +81,64 @@ impl Model {
             layer: None,
             name: None,
             id: 0,
+            frames: vec![],
+            voxel_index: None,
+            strict: false,
+        }
+    }
+
+    /// Creates a new model with no voxels. Same as new() but reads better when making a blank model to fill in.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let canvas = Model::empty(10, 10, 10);
+    /// assert_eq!(true, canvas.is_empty());
+    /// ```
+    pub fn empty(x: u16, y: u16, z: u16) -> Model {
+        Model::new(x, y, z)
+    }
+
+    /// Creates a model with the voxels given that is just large enough to hold them, so the size on each
+    /// axis is one more than the largest position. The voxels are not moved, so there can be empty space
+    /// before them. A model without voxels has a size of 1 by 1 by 1.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, Voxel};
+    ///
+    /// let model = Model::from_voxels(vec![Voxel::new(2, 0, 1, 1), Voxel::new(4, 3, 1, 1)]);
+    /// assert_eq!((5, 4, 2), model.size);
+    /// ```
+    pub fn from_voxels(voxels: Vec<Voxel>) -> Model {
+        let mut model = Model::new(1, 1, 1);
+        model.voxels = voxels;
+        if let Some((_, max)) = model.bounding_box() {
+            model.size = (max.0 as u16 + 1, max.1 as u16 + 1, max.2 as u16 + 1);
         }
+        model
     }
 
-    pub(crate) fn write(&self, writer: &mut BufWriter<File>) {
+    /// Like from_voxels() but moves the voxels so the smallest position on each axis is 0, the same as
+    /// auto_size().
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, Voxel};
+    ///
+    /// let model = Model::from_voxels_origin(vec![Voxel::new(2, 0, 1, 1), Voxel::new(4, 3, 1, 1)]);
+    /// assert_eq!((3, 4, 1), model.size);
+    /// assert!(model.is_voxel_at_pos(0, 0, 0));
+    /// ```
+    pub fn from_voxels_origin(voxels: Vec<Voxel>) -> Model {
+        let mut model = Model::new(1, 1, 1);
+        model.voxels = voxels;
+        model.auto_size();
+        model
+    }
+
+    pub(crate) fn write(&self, writer: &mut BufWriter<File>, sorted: bool) -> io::Result<()> {
         let size_slice: &[u8] = &[
             u16_to_array(self.size.0)[0],
             u16_to_array(self.size.0)[1],
@@ -59,51 +153,65 @@ impl Model {
             0,
             0,
         ];
-        write_chunk("SIZE", 12, 0, writer);
+        write_chunk("SIZE", 12, 0, writer)?;
         //writes the slice for size
-        write_slice(writer, size_slice);
+        write_slice(writer, size_slice)?;
 
-        write_chunk("XYZI", ((self.voxels.len() as u32) * 4) + 4, 0, writer);
+        write_chunk("XYZI", ((self.voxels.len() as u32) * 4) + 4, 0, writer)?;
         //number voxels in the voxobject
-        write_slice(writer, &u32_to_array(self.voxels.len() as u32));
+        write_slice(writer, &u32_to_array(self.voxels.len() as u32))?;
         //writes all of the voxels
-        self.write_voxels(writer);
+        self.write_voxels(writer, sorted)
     }
 
-    fn write_voxels(&self, buf_writer: &mut BufWriter<File>) {
-        let mut voxel_slice: Box<Vec<u8>> = Box::new(vec![]);
-        for i in 0..self.voxels.len() {
-            voxel_slice.push(self.voxels[i].position.0);
-            voxel_slice.push(self.voxels[i].position.1);
-            voxel_slice.push(self.voxels[i].position.2);
-            voxel_slice.push(self.voxels[i].color_index);
+    //sorted writes the voxels in (z, y, x) order so the output does not depend on insertion order
+    fn write_voxels(&self, buf_writer: &mut BufWriter<File>, sorted: bool) -> io::Result<()> {
+        let voxels = if sorted {
+            self.sorted_by_zyx()
+        } else {
+            self.voxels.iter().collect()
+        };
+
+        let mut voxel_slice: Vec<u8> = Vec::with_capacity(voxels.len() * 4);
+        for voxel in voxels {
+            voxel_slice.push(voxel.position.0);
+            voxel_slice.push(voxel.position.1);
+            voxel_slice.push(voxel.position.2);
+            voxel_slice.push(voxel.color_index);
         }
-        buf_writer.write(voxel_slice.as_slice()).unwrap();
+        write_slice(buf_writer, &voxel_slice)
     }
 
     //start at size chunk
-    pub(crate) fn read(input: &Vec<u8>, cursor: &mut i32, id: i32) -> Model {
+    pub(crate) fn read(input: &Vec<u8>, cursor: &mut i32, id: i32) -> Result<Model, VoxError> {
         use crate::riff::i32_from_vec;
         *cursor += 12;
-        let size_x = i32_from_vec(input, cursor) as u16;
+        let size_x = i32_from_vec(input, cursor)? as u16;
         *cursor += 4;
-        let size_y = i32_from_vec(input, cursor) as u16;
+        let size_y = i32_from_vec(input, cursor)? as u16;
         *cursor += 4;
-        let size_z = i32_from_vec(input, cursor) as u16;
+        let size_z = i32_from_vec(input, cursor)? as u16;
         *cursor += 16;
 
-        let num_of_voxels = i32_from_vec(input, cursor);
+        let num_of_voxels = i32_from_vec(input, cursor)?;
         *cursor += 4;
         let mut voxels = Vec::new();
         for i in 0..num_of_voxels {
-            let x = input[(*cursor + 4 * i) as usize];
-            let y = input[(*cursor + 1 + 4 * i) as usize];
-            let z = input[(*cursor + 2 + 4 * i) as usize];
-            let index = input[(*cursor + 3 + 4 * i) as usize];
-            voxels.push(Voxel::new(x, y, z, index))
+            let at = (*cursor + 4 * i) as usize;
+            let bytes = input.get(at..at + 4).ok_or_else(|| {
+                VoxError::InvalidFile(format!("voxel {} of model {} is cut off", i, id))
+            })?;
+            //index 0 is empty, so a voxel with it is not a voxel
+            if bytes[3] == 0 {
+                return Err(VoxError::InvalidFile(format!(
+                    "voxel {} of model {} has color index 0",
+                    i, id
+                )));
+            }
+            voxels.push(Voxel::new(bytes[0], bytes[1], bytes[2], bytes[3]))
         }
 
-        Model {
+        Ok(Model {
             size: (size_x, size_y, size_z),
             voxels,
             position: None,
@@ -111,14 +219,22 @@ impl Model {
             layer: None,
             name: None,
             id,
-        }
+            frames: vec![],
+            voxel_index: None,
+            strict: false,
+        })
     }
 
     pub(crate) fn to_node(&self) -> Node {
         let mut attributes = NodeAttributes::new();
         attributes.name = self.name.clone();
         let mut transform_node = Node::new(NodeType::Transform(self.transform_data()), attributes);
-        let shape_node = Node::new(NodeType::Shape(self.id), NodeAttributes::new());
+        let shape_models = if self.frames.is_empty() {
+            vec![ShapeModel::new(self.id)]
+        } else {
+            self.frames.clone()
+        };
+        let shape_node = Node::new(NodeType::Shape(shape_models), NodeAttributes::new());
         transform_node.add_child(shape_node);
 
         transform_node
@@ -128,22 +244,26 @@ impl Model {
     pub(crate) fn transform_data(&self) -> Transform {
         Transform {
             layer: self.layer.unwrap_or_else(|| 0),
-            rotation: match self.rotation {
-                None => None,
-                Some(rot) => Some(rot as i32),
-            },
-            translation: self.position,
+            frames: vec![Frame {
+                rotation: match self.rotation {
+                    None => None,
+                    Some(rot) => Some(rot as i32),
+                },
+                translation: self.position,
+                index: None,
+            }],
         }
     }
 
-    //size in bytes when written
-    pub(crate) fn get_size(&self) -> i32 {
-        self.voxels.len() as i32 * 4 + 4
+    //size in bytes of the SIZE and XYZI chunks when written
+    pub(crate) fn get_size(&self) -> u64 {
+        CHUNK_HEADER_SIZE + 12 + CHUNK_HEADER_SIZE + self.voxels.len() as u64 * 4 + 4
     }
 
     //start of functions for users.
 
-    /// Adds a voxel to the model. It will return an error if the voxel does not fit inside the model.
+    /// Adds a voxel to the model. It will return an error if the voxel does not fit inside the model, if its
+    /// color index is 0, or if the model is strict and there already is a voxel at the position.
     ///
     /// # Example
     /// ```
@@ -153,14 +273,11 @@ impl Model {
     /// let voxel = Voxel::new(4, 2, 2, 10);
     /// vox.models[0].add_voxel(voxel);
     /// ```
-    pub fn add_voxel(&mut self, new_voxel: Voxel) -> Result<(), &str> {
-        if (new_voxel.position.0 + 1) as u16 > self.size.0
-            || (new_voxel.position.1 + 1) as u16 > self.size.1
-            || (new_voxel.position.2 + 1) as u16 > self.size.2
-        {
-            return Err("Voxel position greater than Voxobject size");
-        }
-        self.voxels.push(new_voxel);
+    pub fn add_voxel(&mut self, new_voxel: Voxel) -> Result<(), VoxError> {
+        check_color(new_voxel.color_index)?;
+        let (x, y, z) = new_voxel.position;
+        self.check_can_add(x, y, z)?;
+        self.push_voxel(new_voxel);
         Ok(())
     }
 
@@ -177,23 +294,80 @@ impl Model {
     /// ```
     pub fn clear_voxels(&mut self) {
         self.voxels.clear();
+        self.voxels_changed();
+    }
+
+    /// Makes the model the same as a new model with the same size, so the voxels are removed and the name,
+    /// position, rotation, layer, and animation frames are cleared. The id of the model in its voxfile and
+    /// whether the model is strict are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(2, 2, 2, 10, 10, 10, 1).unwrap();
+    /// vox.models[0].set_name("box");
+    ///
+    /// vox.models[0].reset();
+    /// assert!(vox.models[0].is_empty());
+    /// assert_eq!(None, vox.models[0].name);
+    /// assert_eq!((10, 10, 10), vox.models[0].size);
+    /// ```
+    pub fn reset(&mut self) {
+        *self = Model {
+            id: self.id,
+            strict: self.strict,
+            ..Model::new(self.size.0, self.size.1, self.size.2)
+        };
     }
 
     /// Sets the size of the model. Size must be less than or equal to 256 on all axis.
+    /// Voxels that are outside of the new size are removed.
     ///
     /// # Example
     /// ```
     /// use create_vox::VoxFile;
     ///
     /// let mut vox = VoxFile::new(20,20,20);
+    /// vox.models[0].add_voxel_at_pos(15,1,1,1).unwrap();
     /// vox.models[0].set_size(12,6,24);
     /// assert_eq!(vox.models[0].size, (12, 6, 24));
+    /// assert_eq!(0, vox.models[0].num_of_voxels());
     /// ```
     pub fn set_size(&mut self, x: u16, y: u16, z: u16) {
-        if x > 256 || y > 256 || z > 256 {
-            panic!("size can not be greater than 256");
+        if let Err(error) = self.try_set_size(x, y, z) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Like set_size() but returns an error with the axis that is too large instead of panicking. The model
+    /// is not changed if there is an error.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxError, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(20,20,20);
+    /// vox.models[0].try_set_size(12, 6, 24).unwrap();
+    /// assert_eq!(vox.models[0].size, (12, 6, 24));
+    ///
+    /// let error = vox.models[0].try_set_size(10, 300, 10).unwrap_err();
+    /// assert!(matches!(error, VoxError::SizeTooLarge { axis: Axis::Y, value: 300 }));
+    /// assert_eq!(vox.models[0].size, (12, 6, 24));
+    /// ```
+    pub fn try_set_size(&mut self, x: u16, y: u16, z: u16) -> Result<(), VoxError> {
+        for (axis, value) in [(Axis::X, x), (Axis::Y, y), (Axis::Z, z)].iter() {
+            if *value > 256 {
+                return Err(VoxError::SizeTooLarge {
+                    axis: *axis,
+                    value: *value as u32,
+                });
+            }
         }
         self.size = (x, y, z);
+        self.clamp_to_size();
+        Ok(())
     }
 
     /// Makes the size of the model as small as possible
@@ -207,46 +381,61 @@ impl Model {
     /// vox.models[0].auto_size();
     /// ```
     pub fn auto_size(&mut self) {
-        let mut new_size = (1, 1, 1);
-        let mut smallest_pos: (u8, u8, u8) = (255, 255, 255);
-
-        //get smallest position of the voxels
-        for voxel in self.voxels.iter() {
-            if voxel.position.0 < smallest_pos.0 {
-                smallest_pos.0 = voxel.position.0
+        let (min, max) = match self.bounding_box() {
+            Some(corners) => corners,
+            None => {
+                self.size = (1, 1, 1);
+                return;
             }
-            if voxel.position.1 < smallest_pos.1 {
-                smallest_pos.1 = voxel.position.1
-            }
-            if voxel.position.2 < smallest_pos.2 {
-                smallest_pos.2 = voxel.position.2
-            }
-        }
+        };
+
         //move voxels
         for voxel in self.voxels.iter_mut() {
             voxel.position = (
-                voxel.position.0 - smallest_pos.0,
-                voxel.position.1 - smallest_pos.1,
-                voxel.position.2 - smallest_pos.2,
+                voxel.position.0 - min.0,
+                voxel.position.1 - min.1,
+                voxel.position.2 - min.2,
             )
         }
+        self.voxels_changed();
 
-        for voxel in self.voxels.iter() {
-            if (voxel.position.0 as u16) > new_size.0 - 1 {
-                new_size.0 = (voxel.position.0 + 1) as u16
-            }
-            if (voxel.position.1 as u16) > new_size.1 - 1 {
-                new_size.1 = (voxel.position.1 + 1) as u16
-            }
-            if (voxel.position.2 as u16) > new_size.2 - 1 {
-                new_size.2 = (voxel.position.2 + 1) as u16
-            }
-        }
+        self.size = (
+            (max.0 - min.0) as u16 + 1,
+            (max.1 - min.1) as u16 + 1,
+            (max.2 - min.2) as u16 + 1,
+        );
+    }
 
-        self.size = new_size
+    /// Goes through every position in the box between 2 corners, with both corners included. The x
+    /// position changes the slowest and z the fastest. There are no positions if min is larger than max
+    /// on any axis.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile, Voxel};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// //a checkerboard of 2 colors
+    /// for (x, y, z) in Model::region_coords((0, 0, 0), (3, 3, 3)) {
+    ///     let color = if (x + y + z) % 2 == 0 { 1 } else { 2 };
+    ///     vox.models[0].add_voxel(Voxel::new(x, y, z, color)).unwrap();
+    /// }
+    ///
+    /// assert_eq!(64, vox.models[0].num_of_voxels());
+    /// assert_eq!(1, vox.models[0].get_voxel_at(1, 1, 0).unwrap().color_index);
+    /// assert_eq!(2, vox.models[0].get_voxel_at(1, 1, 1).unwrap().color_index);
+    /// ```
+    pub fn region_coords(
+        min: (u8, u8, u8),
+        max: (u8, u8, u8),
+    ) -> impl Iterator<Item = (u8, u8, u8)> {
+        (min.0..=max.0).flat_map(move |x| {
+            (min.1..=max.1).flat_map(move |y| (min.2..=max.2).map(move |z| (x, y, z)))
+        })
     }
 
-    /// Fills in the area between 2 points with voxels
+    /// Fills in the area between 2 points with voxels. The start is included and the end is not, so the end
+    /// can be up to the size of the model.
     ///
     /// # Example
     /// ```
@@ -264,15 +453,239 @@ impl Model {
         endy: u8,
         endz: u8,
         colorindex: u8,
-    ) -> Result<(), &str> {
-        if endx as u16 > self.size.0 || endx as u16 > self.size.1 || endx as u16 > self.size.2 {
-            return Err("Cube too large");
-        }
-        for currentx in startx..endx {
-            for currenty in starty..endy {
-                for currentz in startz..endz {
-                    self.add_voxel(Voxel::new(currentx, currenty, currentz, colorindex))
-                        .unwrap();
+    ) -> Result<(), VoxError> {
+        check_color(colorindex)?;
+        if endx as u16 > self.size.0 || endy as u16 > self.size.1 || endz as u16 > self.size.2 {
+            //the end is not filled so the last voxel is the one outside of the model
+            return Err(VoxError::VoxelOutOfBounds {
+                pos: (
+                    endx.saturating_sub(1),
+                    endy.saturating_sub(1),
+                    endz.saturating_sub(1),
+                ),
+                size: self.size,
+            });
+        }
+        check_range((startx, starty, startz), (endx, endy, endz))?;
+        if startx == endx || starty == endy || startz == endz {
+            return Ok(());
+        }
+        for (x, y, z) in
+            Model::region_coords((startx, starty, startz), (endx - 1, endy - 1, endz - 1))
+        {
+            self.push_voxel(Voxel::new(x, y, z, colorindex));
+        }
+
+        Ok(())
+    }
+
+    /// Adds voxels along the 12 edges of the box between 2 corners. Both corners are included
+    /// and the faces and inside of the box are left empty.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_box_frame((1, 1, 1), (5, 6, 7), 1).unwrap();
+    ///
+    /// // 4 edges along each axis with the 8 corners only counted once
+    /// assert_eq!(4 * (5 + 6 + 7) - 16, vox.models[0].num_of_voxels());
+    /// // the center of a face is empty
+    /// assert_eq!(false, vox.models[0].is_voxel_at_pos(3, 3, 1));
+    /// ```
+    pub fn add_box_frame(
+        &mut self,
+        min: (u8, u8, u8),
+        max: (u8, u8, u8),
+        colorindex: u8,
+    ) -> Result<(), VoxError> {
+        check_color(colorindex)?;
+        self.check_in_bounds(max.0, max.1, max.2)?;
+        check_range(min, max)?;
+        for (x, y, z) in Model::region_coords(min, max) {
+            //a voxel is on an edge when it is on the side of the box on at least 2 axis
+            let sides = (x == min.0 || x == max.0) as u8
+                + (y == min.1 || y == max.1) as u8
+                + (z == min.2 || z == max.2) as u8;
+            if sides >= 2 {
+                self.push_voxel(Voxel::new(x, y, z, colorindex));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds voxels on the 6 faces of the box between 2 corners, leaving the inside of the box empty. Both
+    /// corners are included.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube_shell((0, 0, 0), (4, 4, 4), 1).unwrap();
+    ///
+    /// // the 5 by 5 by 5 box without its 3 by 3 by 3 inside
+    /// assert_eq!(5 * 5 * 5 - 3 * 3 * 3, vox.models[0].num_of_voxels());
+    /// assert_eq!(false, vox.models[0].is_voxel_at_pos(2, 2, 2));
+    /// assert_eq!(true, vox.models[0].is_voxel_at_pos(2, 2, 4));
+    /// ```
+    pub fn add_cube_shell(
+        &mut self,
+        min: (u8, u8, u8),
+        max: (u8, u8, u8),
+        colorindex: u8,
+    ) -> Result<(), VoxError> {
+        check_color(colorindex)?;
+        self.check_in_bounds(max.0, max.1, max.2)?;
+        check_range(min, max)?;
+        for (x, y, z) in Model::region_coords(min, max) {
+            if x == min.0 || x == max.0 || y == min.1 || y == max.1 || z == min.2 || z == max.2 {
+                self.push_voxel(Voxel::new(x, y, z, colorindex));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds a sphere of voxels around the center. Every voxel that is within the radius of the center is
+    /// filled, or only the voxels in the outer one voxel thick layer when hollow is true. The sphere can
+    /// go past 0 on any axis and those voxels are left out, but it can not go past the size of the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_sphere((5, 5, 5), 3, 1, false).unwrap();
+    /// assert!(vox.models[0].is_voxel_at_pos(5, 5, 5));
+    /// assert!(vox.models[0].is_voxel_at_pos(5, 5, 8));
+    /// assert!(!vox.models[0].is_voxel_at_pos(7, 7, 7));
+    ///
+    /// vox.models[0].clear_voxels();
+    /// vox.models[0].add_sphere((5, 5, 5), 3, 1, true).unwrap();
+    /// assert!(!vox.models[0].is_voxel_at_pos(5, 5, 5));
+    ///
+    /// assert!(vox.models[0].add_sphere((5, 5, 5), 5, 1, false).is_err());
+    /// ```
+    pub fn add_sphere(
+        &mut self,
+        center: (u8, u8, u8),
+        radius: u8,
+        colorindex: u8,
+        hollow: bool,
+    ) -> Result<(), VoxError> {
+        check_color(colorindex)?;
+        let far = (
+            center.0 as u16 + radius as u16,
+            center.1 as u16 + radius as u16,
+            center.2 as u16 + radius as u16,
+        );
+        if far.0 >= self.size.0 || far.1 >= self.size.1 || far.2 >= self.size.2 {
+            //positions past 255 can not be shown, so they are shown as 255
+            return Err(VoxError::VoxelOutOfBounds {
+                pos: (
+                    far.0.min(255) as u8,
+                    far.1.min(255) as u8,
+                    far.2.min(255) as u8,
+                ),
+                size: self.size,
+            });
+        }
+
+        let radius_f = radius as f32;
+        for currentx in center.0.saturating_sub(radius)..=center.0 + radius {
+            for currenty in center.1.saturating_sub(radius)..=center.1 + radius {
+                for currentz in center.2.saturating_sub(radius)..=center.2 + radius {
+                    let dx = currentx as f32 - center.0 as f32;
+                    let dy = currenty as f32 - center.1 as f32;
+                    let dz = currentz as f32 - center.2 as f32;
+                    let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+
+                    if distance <= radius_f && (!hollow || distance > radius_f - 1.0) {
+                        self.push_voxel(Voxel::new(currentx, currenty, currentz, colorindex));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds a cylinder of voxels that starts at the base center and goes height voxels along the axis.
+    /// Every voxel within the radius of the center line is filled, or only the one voxel thick wall when
+    /// hollow is true, which leaves both ends open like a pipe. Like add_sphere(), the cylinder can go past
+    /// 0 on the axes other than the one it goes along, but it can not go past the size of the model, and
+    /// color_index can not be 0. Nothing is added if it returns an error.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cylinder((5, 0, 5), 3, 8, Axis::Y, 1, false).unwrap();
+    /// assert!(vox.models[0].is_voxel_at_pos(5, 7, 5));
+    /// assert!(vox.models[0].is_voxel_at_pos(8, 0, 5));
+    /// assert!(!vox.models[0].is_voxel_at_pos(5, 8, 5));
+    ///
+    /// assert!(vox.models[0].add_cylinder((5, 5, 5), 3, 8, Axis::Y, 1, false).is_err());
+    /// ```
+    pub fn add_cylinder(
+        &mut self,
+        base_center: (u8, u8, u8),
+        radius: u8,
+        height: u8,
+        axis: Axis,
+        color_index: u8,
+        hollow: bool,
+    ) -> Result<(), VoxError> {
+        check_color(color_index)?;
+        let along = axis.index();
+        let (first, second) = match axis {
+            Axis::X => (1, 2),
+            Axis::Y => (0, 2),
+            Axis::Z => (0, 1),
+        };
+        let base = [base_center.0, base_center.1, base_center.2];
+
+        let mut far = [base[0] as u16, base[1] as u16, base[2] as u16];
+        far[first] += radius as u16;
+        far[second] += radius as u16;
+        far[along] += (height as u16).saturating_sub(1);
+        if far[0] >= self.size.0 || far[1] >= self.size.1 || far[2] >= self.size.2 {
+            //positions past 255 can not be shown, so they are shown as 255
+            return Err(VoxError::VoxelOutOfBounds {
+                pos: (
+                    far[0].min(255) as u8,
+                    far[1].min(255) as u8,
+                    far[2].min(255) as u8,
+                ),
+                size: self.size,
+            });
+        }
+
+        let radius_f = radius as f32;
+        for a in base[first].saturating_sub(radius)..=base[first] + radius {
+            for b in base[second].saturating_sub(radius)..=base[second] + radius {
+                let da = a as f32 - base[first] as f32;
+                let db = b as f32 - base[second] as f32;
+                let distance = (da * da + db * db).sqrt();
+                if distance > radius_f || (hollow && distance <= radius_f - 1.0) {
+                    continue;
+                }
+
+                for step in 0..height {
+                    let mut position = [0; 3];
+                    position[along] = base[along] + step;
+                    position[first] = a;
+                    position[second] = b;
+                    self.push_voxel(Voxel::new(
+                        position[0],
+                        position[1],
+                        position[2],
+                        color_index,
+                    ));
                 }
             }
         }
@@ -280,7 +693,9 @@ impl Model {
         Ok(())
     }
 
-    /// Checks if there is a voxel at the position
+    /// Checks if there is a voxel at the position. This goes through all of the voxels, so it is meant for
+    /// one-off checks. When checking a lot of positions, make an OccupancyMap with occupancy_map() once and
+    /// use it instead.
     ///
     /// # Example
     /// ```
@@ -299,17 +714,91 @@ impl Model {
         false
     }
 
-    //needs testing
-    fn check_voxels_pos(&mut self) {
+    /// Builds an index of the voxel positions so get_voxel_at() does not need to go through all of the
+    /// voxels. Methods of the model keep the index up to date, but it needs to be built again after
+    /// changing `voxels` directly.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].build_index();
+    /// vox.models[0].add_voxel_at_pos(3, 4, 3, 1).unwrap();
+    /// assert!(vox.models[0].get_voxel_at(3, 4, 3).is_some());
+    /// ```
+    pub fn build_index(&mut self) {
+        self.voxel_index = Some(self.occupancy_map());
+    }
+
+    /// Gets the voxel at the position. If there is more than one voxel at the position the last one is
+    /// used. This goes through all of the voxels unless build_index() has been called.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(3, 4, 3, 7).unwrap();
+    /// assert_eq!(7, vox.models[0].get_voxel_at(3, 4, 3).unwrap().color_index);
+    /// assert!(vox.models[0].get_voxel_at(3, 3, 3).is_none());
+    /// ```
+    pub fn get_voxel_at(&self, x: u8, y: u8, z: u8) -> Option<&Voxel> {
+        match &self.voxel_index {
+            Some(index) => index
+                .index_of((x, y, z))
+                .map(|voxel_index| &self.voxels[voxel_index]),
+            None => self
+                .voxels
+                .iter()
+                .rev()
+                .find(|voxel| voxel.position == (x, y, z)),
+        }
+    }
+
+    //adds a voxel and keeps the index up to date
+    fn push_voxel(&mut self, voxel: Voxel) {
+        if let Some(index) = &mut self.voxel_index {
+            index.insert(voxel.position, self.voxels.len());
+        }
+        self.voxels.push(voxel);
+    }
+
+    //builds the index again if there is one, used after voxels are removed or moved
+    fn voxels_changed(&mut self) {
+        if self.voxel_index.is_some() {
+            self.build_index();
+        }
+    }
+
+    /// Removes all of the voxels that are outside of the size of the model and returns how many were removed.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(1,1,1,1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(8,1,1,1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(9,9,9,1).unwrap();
+    ///
+    /// vox.models[0].size = (5, 10, 10);
+    /// assert_eq!(2, vox.models[0].clamp_to_size());
+    /// assert_eq!(1, vox.models[0].num_of_voxels());
+    /// ```
+    pub fn clamp_to_size(&mut self) -> usize {
         let size = self.size;
+        let num_before = self.voxels.len();
         self.voxels.retain(|voxel| {
             (voxel.position.0 as u16) < size.0
                 && (voxel.position.1 as u16) < size.1
                 && (voxel.position.2 as u16) < size.2
         });
+        self.voxels_changed();
+        num_before - self.voxels.len()
     }
 
-    /// Adds a voxel at certain position
+    /// Adds a voxel at certain position. Like add_voxel(), a color index of 0 returns an error.
     ///
     /// # Example
     /// ```
@@ -325,15 +814,15 @@ impl Model {
     ///
     /// assert_eq!(2, vox.models[0].num_of_voxels());
     /// ```
-    pub fn add_voxel_at_pos(&mut self, x: u8, y: u8, z: u8, voxel_index: u8) -> Result<(), &str> {
-        if (x + 1) as u16 > self.size.0
-            || (y + 1) as u16 > self.size.1
-            || (z + 1) as u16 > self.size.2
-        {
-            return Err("Position greater than Voxobject size");
-        }
-        self.voxels.push(Voxel::new(x, y, z, voxel_index));
-        Ok(())
+    pub fn add_voxel_at_pos(
+        &mut self,
+        x: u8,
+        y: u8,
+        z: u8,
+        voxel_index: u8,
+    ) -> Result<(), VoxError> {
+        check_color(voxel_index)?;
+        self.add_voxel(Voxel::new(x, y, z, voxel_index))
     }
 
     /// Returns the number of voxels in the model
@@ -354,6 +843,39 @@ impl Model {
         self.voxels.len() as i32
     }
 
+    /// Returns true if the model does not have any voxels
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// assert_eq!(true, vox.models[0].is_empty());
+    /// vox.models[0].add_voxel_at_pos(1,1,1,1).unwrap();
+    /// assert_eq!(false, vox.models[0].is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// Returns true if the model has more voxels than MagicaVoxel can work with well, so it should be split
+    /// into smaller models. A model can hold at most 256 * 256 * 256 (16,777,216) voxels without two in the
+    /// same place, but MagicaVoxel becomes slow to load and edit models long before that, so more than
+    /// 4,194,304 (a quarter of that) counts as oversized. The file format itself allows about a billion
+    /// voxels in a model, since the voxels are 4 bytes each in a chunk that can be 4 GiB.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(256, 256, 256);
+    /// vox.models[0].add_cube(0, 0, 0, 100, 100, 100, 1).unwrap();
+    /// assert!(!vox.models[0].is_oversized());
+    /// ```
+    pub fn is_oversized(&self) -> bool {
+        self.voxels.len() > OVERSIZED_VOXELS
+    }
+
     /// Keeps all of the voxels in the Voxobject that return true with the closure given
     ///
     /// # Example
@@ -375,6 +897,7 @@ impl Model {
         T: FnMut(&Voxel) -> bool,
     {
         self.voxels.retain(closure);
+        self.voxels_changed();
     }
 
     /// Changes all the voxels in the Voxobject with the closure
@@ -401,9 +924,2083 @@ impl Model {
         for voxel in voxel_iter {
             closure(voxel);
         }
+        //the closure can move voxels
+        self.voxels_changed();
+    }
+
+    /// Sets the name of the model, which is saved as the name of its node in the file.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].set_name("tree");
+    /// assert_eq!(Some(String::from("tree")), vox.models[0].name);
+    /// ```
+    pub fn set_name(&mut self, name: &str) {
+        self.name = Some(String::from(name));
     }
 
     pub fn get_id(&self)-> i32{
         self.id
     }
+
+    /// Returns the ids of the models shown for each keyframe when the model is animated. The first
+    /// id is the model itself. It is empty when the model is not animated.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// assert!(vox.models[0].get_frame_ids().is_empty());
+    ///
+    /// vox.add_palette_cycle_animation(0, 3, (1, 3)).unwrap();
+    /// assert_eq!(vec![0, 1, 2], vox.models[0].get_frame_ids());
+    /// ```
+    pub fn get_frame_ids(&self) -> Vec<i32> {
+        self.frames.iter().map(|frame| frame.model_id).collect()
+    }
+
+    /// Checks if the bounding boxes of 2 models overlap when they are placed at the positions
+    /// given. The position of a model is where its (0, 0, 0) corner is and the box covers its whole size.
+    /// Boxes that only share a side do not overlap.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let first = Model::new(4, 4, 4);
+    /// let second = Model::new(4, 4, 4);
+    ///
+    /// assert_eq!(true, first.aabb_overlaps((0, 0, 0), &second, (3, 3, 3)));
+    /// // touching sides
+    /// assert_eq!(false, first.aabb_overlaps((0, 0, 0), &second, (4, 0, 0)));
+    /// // separated
+    /// assert_eq!(false, first.aabb_overlaps((0, 0, 0), &second, (10, 0, 0)));
+    /// ```
+    pub fn aabb_overlaps(
+        &self,
+        self_pos: (i32, i32, i32),
+        other: &Model,
+        other_pos: (i32, i32, i32),
+    ) -> bool {
+        let overlaps_on_axis =
+            |self_start: i32, self_size: u16, other_start: i32, other_size: u16| {
+                let self_end = self_start as i64 + self_size as i64;
+                let other_end = other_start as i64 + other_size as i64;
+                (self_start as i64) < other_end && (other_start as i64) < self_end
+            };
+
+        overlaps_on_axis(self_pos.0, self.size.0, other_pos.0, other.size.0)
+            && overlaps_on_axis(self_pos.1, self.size.1, other_pos.1, other.size.1)
+            && overlaps_on_axis(self_pos.2, self.size.2, other_pos.2, other.size.2)
+    }
+
+    /// Checks if any voxel of one model is in the same place as a voxel of the other model when
+    /// they are placed at the positions given. The bounding boxes are checked first with aabb_overlaps().
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut even = Model::new(4, 4, 4);
+    /// let mut odd = Model::new(4, 4, 4);
+    /// for x in 0..4 {
+    ///     if x % 2 == 0 {
+    ///         even.add_voxel_at_pos(x, 0, 0, 1).unwrap();
+    ///     } else {
+    ///         odd.add_voxel_at_pos(x, 0, 0, 2).unwrap();
+    ///     }
+    /// }
+    ///
+    /// // the voxels fit between each other
+    /// assert_eq!(false, even.collides_with((0, 0, 0), &odd, (0, 0, 0)));
+    /// assert_eq!(true, even.collides_with((0, 0, 0), &odd, (1, 0, 0)));
+    /// ```
+    pub fn collides_with(
+        &self,
+        self_pos: (i32, i32, i32),
+        other: &Model,
+        other_pos: (i32, i32, i32),
+    ) -> bool {
+        if self.is_empty() || other.is_empty() || !self.aabb_overlaps(self_pos, other, other_pos) {
+            return false;
+        }
+
+        let world_pos = |voxel: &Voxel, pos: (i32, i32, i32)| {
+            (
+                voxel.position.0 as i64 + pos.0 as i64,
+                voxel.position.1 as i64 + pos.1 as i64,
+                voxel.position.2 as i64 + pos.2 as i64,
+            )
+        };
+
+        let occupied: HashSet<(i64, i64, i64)> = self
+            .voxels
+            .iter()
+            .map(|voxel| world_pos(voxel, self_pos))
+            .collect();
+
+        other
+            .voxels
+            .iter()
+            .any(|voxel| occupied.contains(&world_pos(voxel, other_pos)))
+    }
+
+    //returns an error if the position is outside of the model
+    //checks everything add_voxel() does, so a caller can fail before changing anything else
+    pub(crate) fn check_can_add(&self, x: u8, y: u8, z: u8) -> Result<(), VoxError> {
+        self.check_in_bounds(x, y, z)?;
+        if self.strict && self.get_voxel_at(x, y, z).is_some() {
+            return Err(VoxError::DuplicatePosition((x, y, z)));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_in_bounds(&self, x: u8, y: u8, z: u8) -> Result<(), VoxError> {
+        if x as u16 >= self.size.0 || y as u16 >= self.size.1 || z as u16 >= self.size.2 {
+            return Err(VoxError::VoxelOutOfBounds {
+                pos: (x, y, z),
+                size: self.size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Fills a column of voxels going up the z axis at x and y. Both z_start and z_end are filled. It will
+    /// return an error if z_start is after z_end, if the column goes outside of the model, or if colorindex
+    /// is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].fill_column(2, 3, 1, 6, 1).unwrap();
+    /// assert_eq!(6, vox.models[0].num_of_voxels());
+    /// for z in 1..=6 {
+    ///     assert!(vox.models[0].is_voxel_at_pos(2, 3, z));
+    /// }
+    ///
+    /// assert!(vox.models[0].fill_column(2, 3, 5, 10, 1).is_err());
+    /// ```
+    pub fn fill_column(
+        &mut self,
+        x: u8,
+        y: u8,
+        z_start: u8,
+        z_end: u8,
+        colorindex: u8,
+    ) -> Result<(), VoxError> {
+        check_color(colorindex)?;
+        if z_start > z_end {
+            return Err(VoxError::InvalidRange {
+                start: z_start,
+                end: z_end,
+            });
+        }
+        self.check_in_bounds(x, y, z_end)?;
+
+        for z in z_start..=z_end {
+            self.push_voxel(Voxel::new(x, y, z, colorindex));
+        }
+        Ok(())
+    }
+
+    /// Moves the voxels out of the model without copying them, leaving the model empty.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(0, 0, 0, 2, 2, 2, 1).unwrap();
+    ///
+    /// let mut voxels = vox.models[0].take_voxels();
+    /// assert!(vox.models[0].is_empty());
+    ///
+    /// voxels.iter_mut().for_each(|voxel| voxel.color_index = 5);
+    /// vox.models[0].set_voxels(voxels).unwrap();
+    /// assert_eq!(8, vox.models[0].num_of_voxels());
+    /// ```
+    pub fn take_voxels(&mut self) -> Vec<Voxel> {
+        let voxels = std::mem::take(&mut self.voxels);
+        self.voxels_changed();
+        voxels
+    }
+
+    /// Replaces the voxels of the model. It will return an error and keep the old voxels if any of the voxels
+    /// do not fit inside the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{VoxFile, Voxel};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].set_voxels(vec![Voxel::new(1, 1, 1, 1), Voxel::new(2, 1, 1, 1)]).unwrap();
+    /// assert_eq!(2, vox.models[0].num_of_voxels());
+    ///
+    /// assert!(vox.models[0].set_voxels(vec![Voxel::new(12, 1, 1, 1)]).is_err());
+    /// assert_eq!(2, vox.models[0].num_of_voxels());
+    /// ```
+    pub fn set_voxels(&mut self, voxels: Vec<Voxel>) -> Result<(), VoxError> {
+        for voxel in voxels.iter() {
+            self.check_in_bounds(voxel.position.0, voxel.position.1, voxel.position.2)?;
+        }
+        self.voxels = voxels;
+        self.voxels_changed();
+        Ok(())
+    }
+
+    /// Gets the smallest and largest position of the voxels on each axis, which are the corners of the
+    /// smallest box that has all of the voxels in it. Both corners are in the box. Returns None if there
+    /// are no voxels.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// assert_eq!(None, vox.models[0].bounding_box());
+    ///
+    /// vox.models[0].add_cube(2, 3, 4, 5, 5, 5, 1).unwrap();
+    /// assert_eq!(Some(((2, 3, 4), (4, 4, 4))), vox.models[0].bounding_box());
+    /// ```
+    pub fn bounding_box(&self) -> Option<Corners> {
+        let first = self.voxels.first()?.position;
+        let mut min = first;
+        let mut max = first;
+        for voxel in self.voxels.iter() {
+            min.0 = min.0.min(voxel.position.0);
+            min.1 = min.1.min(voxel.position.1);
+            min.2 = min.2.min(voxel.position.2);
+            max.0 = max.0.max(voxel.position.0);
+            max.1 = max.1.max(voxel.position.1);
+            max.2 = max.2.max(voxel.position.2);
+        }
+        Some((min, max))
+    }
+
+    /// Moves all of the voxels so that the smallest position on each axis is at min_corner. It will return
+    /// an error and leave the voxels where they are if any of them would be moved outside of the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(5, 6, 7, 8, 8, 9, 1).unwrap();
+    /// vox.models[0].translate_to((2, 2, 2)).unwrap();
+    /// assert!(vox.models[0].is_voxel_at_pos(2, 2, 2));
+    /// assert!(vox.models[0].is_voxel_at_pos(4, 3, 3));
+    ///
+    /// assert!(vox.models[0].translate_to((8, 0, 0)).is_err());
+    /// ```
+    pub fn translate_to(&mut self, min_corner: (u8, u8, u8)) -> Result<(), VoxError> {
+        let (min, max) = match self.bounding_box() {
+            Some(corners) => corners,
+            None => return Ok(()),
+        };
+
+        let new_max = (
+            max.0 as u16 - min.0 as u16 + min_corner.0 as u16,
+            max.1 as u16 - min.1 as u16 + min_corner.1 as u16,
+            max.2 as u16 - min.2 as u16 + min_corner.2 as u16,
+        );
+        if new_max.0 >= self.size.0 || new_max.1 >= self.size.1 || new_max.2 >= self.size.2 {
+            return Err(VoxError::VoxelOutOfBounds {
+                pos: (
+                    new_max.0.min(255) as u8,
+                    new_max.1.min(255) as u8,
+                    new_max.2.min(255) as u8,
+                ),
+                size: self.size,
+            });
+        }
+
+        for voxel in self.voxels.iter_mut() {
+            voxel.position = (
+                voxel.position.0 - min.0 + min_corner.0,
+                voxel.position.1 - min.1 + min_corner.1,
+                voxel.position.2 - min.2 + min_corner.2,
+            );
+        }
+        self.voxels_changed();
+        Ok(())
+    }
+
+    /// Moves all of the voxels by the offset given. It will return an error and leave the voxels where they
+    /// are if any of them would be moved below 0 or outside of the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(5, 5, 5, 1).unwrap();
+    /// vox.models[0].translate(-2, 0, 3).unwrap();
+    /// assert!(vox.models[0].is_voxel_at_pos(3, 5, 8));
+    ///
+    /// assert!(vox.models[0].translate(0, -6, 0).is_err());
+    /// assert!(vox.models[0].is_voxel_at_pos(3, 5, 8));
+    /// ```
+    pub fn translate(&mut self, dx: i32, dy: i32, dz: i32) -> Result<(), VoxError> {
+        let mut moved = Vec::with_capacity(self.voxels.len());
+        for voxel in self.voxels.iter() {
+            let position = (
+                voxel.position.0 as i64 + dx as i64,
+                voxel.position.1 as i64 + dy as i64,
+                voxel.position.2 as i64 + dz as i64,
+            );
+            let inside = |value: i64, size: u16| value >= 0 && value < size as i64;
+            if !inside(position.0, self.size.0)
+                || !inside(position.1, self.size.1)
+                || !inside(position.2, self.size.2)
+            {
+                //positions below 0 or past 255 can not be shown, so they are shown as 0 or 255
+                return Err(VoxError::VoxelOutOfBounds {
+                    pos: (
+                        position.0.clamp(0, 255) as u8,
+                        position.1.clamp(0, 255) as u8,
+                        position.2.clamp(0, 255) as u8,
+                    ),
+                    size: self.size,
+                });
+            }
+            moved.push((position.0 as u8, position.1 as u8, position.2 as u8));
+        }
+
+        for (voxel, position) in self.voxels.iter_mut().zip(moved) {
+            voxel.position = position;
+        }
+        self.voxels_changed();
+        Ok(())
+    }
+
+    /// Smooths the model by making each position filled if most of its 26 neighbors are filled
+    /// and empty if they are not. Positions that get filled use the color most of their neighbors have.
+    /// This is repeated for the number of iterations given.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(20,20,20);
+    /// vox.models[0].add_cube(2, 2, 2, 9, 9, 9, 1).unwrap();
+    /// //a stray voxel away from the cube
+    /// vox.models[0].add_voxel_at_pos(15, 15, 15, 2).unwrap();
+    ///
+    /// vox.models[0].smooth(1);
+    /// assert_eq!(false, vox.models[0].is_voxel_at_pos(15, 15, 15));
+    /// assert!(vox.models[0].is_voxel_at_pos(5, 5, 5));
+    /// ```
+    pub fn smooth(&mut self, iterations: u8) {
+        for _ in 0..iterations {
+            let occupancy = self.occupancy_map();
+            let color_at = |position: (u8, u8, u8)| {
+                occupancy
+                    .index_of(position)
+                    .map(|index| self.voxels[index].color_index)
+            };
+
+            //only positions with a voxel next to them can change
+            let mut candidates: HashSet<(u8, u8, u8)> = HashSet::new();
+            for voxel in self.voxels.iter() {
+                for neighbor in self.neighbors_26(voxel.position) {
+                    candidates.insert(neighbor);
+                }
+                candidates.insert(voxel.position);
+            }
+            let mut candidates: Vec<(u8, u8, u8)> = candidates.into_iter().collect();
+            candidates.sort_unstable_by_key(|pos| (pos.2, pos.1, pos.0));
+
+            let mut smoothed = Vec::new();
+            for position in candidates {
+                let mut votes = [0u8; 256];
+                let mut filled = 0;
+                for neighbor in self.neighbors_26(position) {
+                    if let Some(color) = color_at(neighbor) {
+                        votes[color as usize] += 1;
+                        filled += 1;
+                    }
+                }
+                if filled <= 13 {
+                    continue;
+                }
+
+                let color = match color_at(position) {
+                    Some(color) => color,
+                    //the lowest color index wins ties
+                    None => (1..=255u8)
+                        .max_by_key(|color| (votes[*color as usize], 255 - color))
+                        .unwrap(),
+                };
+                smoothed.push(Voxel::new(position.0, position.1, position.2, color));
+            }
+            self.voxels = smoothed;
+            self.voxels_changed();
+        }
+    }
+
+    //the positions around a position that are inside of the model
+    fn neighbors_26(&self, position: (u8, u8, u8)) -> Vec<(u8, u8, u8)> {
+        let mut neighbors = Vec::with_capacity(26);
+        for dx in -1i16..=1 {
+            for dy in -1i16..=1 {
+                for dz in -1i16..=1 {
+                    if dx == 0 && dy == 0 && dz == 0 {
+                        continue;
+                    }
+                    let x = position.0 as i16 + dx;
+                    let y = position.1 as i16 + dy;
+                    let z = position.2 as i16 + dz;
+                    if x >= 0
+                        && y >= 0
+                        && z >= 0
+                        && (x as u16) < self.size.0
+                        && (y as u16) < self.size.1
+                        && (z as u16) < self.size.2
+                    {
+                        neighbors.push((x as u8, y as u8, z as u8));
+                    }
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// Makes a new model where every voxel is moved along shear_axis by its position on axis times the factor.
+    /// A factor of 0.5 makes the 2:1 slope used for isometric art. The new model is made larger to fit the
+    /// moved voxels. Returns `VoxError::SameAxis` if axis and shear_axis are the same.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(1,1,4);
+    /// vox.models[0].fill_column(0, 0, 0, 3, 1).unwrap();
+    ///
+    /// let sheared = vox.models[0].shear(Axis::Z, Axis::X, 1.0).unwrap();
+    /// assert_eq!((4, 1, 4), sheared.size);
+    /// for z in 0..4 {
+    ///     assert!(sheared.is_voxel_at_pos(z, 0, z));
+    /// }
+    /// assert!(vox.models[0].shear(Axis::Z, Axis::Z, 1.0).is_err());
+    /// ```
+    pub fn shear(&self, axis: Axis, shear_axis: Axis, factor: f32) -> Result<Model, VoxError> {
+        if axis == shear_axis {
+            return Err(VoxError::SameAxis(axis));
+        }
+        let size = [self.size.0, self.size.1, self.size.2];
+        let offset = |position: u8| (position as f32 * factor).round() as i64;
+
+        let last = size[axis.index()].max(1) - 1;
+        let min_offset = offset(0).min(offset(last as u8));
+        let max_offset = offset(0).max(offset(last as u8));
+
+        let mut new_size = size;
+        let grown = size[shear_axis.index()] as i64 + max_offset - min_offset;
+        if grown > 256 {
+            return Err(VoxError::SizeTooLarge {
+                axis: shear_axis,
+                value: grown as u32,
+            });
+        }
+        new_size[shear_axis.index()] = grown as u16;
+
+        let mut sheared = self.clone();
+        sheared.size = (new_size[0], new_size[1], new_size[2]);
+        for voxel in sheared.voxels.iter_mut() {
+            let mut position = [voxel.position.0, voxel.position.1, voxel.position.2];
+            let moved =
+                position[shear_axis.index()] as i64 + offset(position[axis.index()]) - min_offset;
+            position[shear_axis.index()] = moved as u8;
+            voxel.position = (position[0], position[1], position[2]);
+        }
+        sheared.voxels_changed();
+        Ok(sheared)
+    }
+
+    /// Makes an OccupancyMap of the voxels in the model. Use it instead of is_voxel_at_pos() when checking
+    /// a lot of positions, since it only goes through the voxels once.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_voxel_at_pos(3, 4, 3, 1).unwrap();
+    ///
+    /// let occupancy = vox.models[0].occupancy_map();
+    /// assert!(occupancy.contains((3, 4, 3)));
+    /// assert_eq!(Some(0), occupancy.index_of((3, 4, 3)));
+    /// ```
+    pub fn occupancy_map(&self) -> OccupancyMap {
+        OccupancyMap::new(&self.voxels)
+    }
+
+    /// Same as occupancy_map(). Build it once and use contains() on it instead of calling
+    /// is_voxel_at_pos() in a loop.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 1).unwrap();
+    ///
+    /// let occupancy = vox.models[0].build_occupancy();
+    /// for x in 0..10 {
+    ///     assert_eq!(x < 5, occupancy.contains((x, 2, 2)));
+    /// }
+    /// ```
+    pub fn build_occupancy(&self) -> OccupancyMap {
+        self.occupancy_map()
+    }
+
+    /// Makes a VoxelGrid of the model, which gets the color of the voxel at a position without going
+    /// through all of the voxels. It allocates memory for each voxel in the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_voxel_at_pos(3, 4, 3, 7).unwrap();
+    ///
+    /// let grid = vox.models[0].grid();
+    /// assert_eq!(Some(7), grid.get(3, 4, 3));
+    /// assert_eq!((10, 10, 10), grid.size());
+    /// ```
+    pub fn grid(&self) -> VoxelGrid {
+        VoxelGrid::new(self.size, &self.voxels)
+    }
+
+    /// Packs each voxel into a u32 as `x | (y << 8) | (z << 16) | (color << 24)`, in the same order as the
+    /// voxels of the model. This is useful for uploading the model as an instance buffer.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(3, 4, 5, 6).unwrap();
+    ///
+    /// let packed = vox.models[0].packed_positions()[0];
+    /// assert_eq!(3, packed & 0xff);
+    /// assert_eq!(4, (packed >> 8) & 0xff);
+    /// assert_eq!(5, (packed >> 16) & 0xff);
+    /// assert_eq!(6, packed >> 24);
+    /// ```
+    pub fn packed_positions(&self) -> Vec<u32> {
+        self.voxels
+            .iter()
+            .map(|voxel| {
+                voxel.position.0 as u32
+                    | (voxel.position.1 as u32) << 8
+                    | (voxel.position.2 as u32) << 16
+                    | (voxel.color_index as u32) << 24
+            })
+            .collect()
+    }
+
+    /// Adds the voxels from an iterator one at a time. If a voxel does not fit inside the model it is not
+    /// added and an error is returned, but the voxels before it stay in the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{VoxFile, Voxel};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// let floor = (0..10)
+    ///     .flat_map(|x| (0..10).map(move |y| Voxel::new(x, y, 0, 1)))
+    ///     .filter(|voxel| (voxel.position.0 + voxel.position.1) % 2 == 0);
+    /// vox.models[0].extend(floor).unwrap();
+    /// assert_eq!(50, vox.models[0].num_of_voxels());
+    ///
+    /// let voxels = vec![Voxel::new(1, 1, 1, 1), Voxel::new(10, 1, 1, 1), Voxel::new(2, 1, 1, 1)];
+    /// assert!(vox.models[0].extend(voxels).is_err());
+    /// assert_eq!(51, vox.models[0].num_of_voxels());
+    /// ```
+    pub fn extend(&mut self, iter: impl IntoIterator<Item = Voxel>) -> Result<(), VoxError> {
+        for voxel in iter {
+            self.check_in_bounds(voxel.position.0, voxel.position.1, voxel.position.2)?;
+            self.push_voxel(voxel);
+        }
+        Ok(())
+    }
+
+    /// Adds a reflected copy of the voxels past the far side of the model on the axis, doubling the size of
+    /// the model on that axis. It will return an error and leave the model as it is if the new size would
+    /// be greater than 256. Voxels that are outside of the size of the model are not copied.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(4,2,2);
+    /// vox.models[0].add_voxel_at_pos(0, 1, 0, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(1, 0, 1, 2).unwrap();
+    ///
+    /// vox.models[0].mirror_copy(Axis::X).unwrap();
+    /// assert_eq!((8, 2, 2), vox.models[0].size);
+    /// assert_eq!(4, vox.models[0].num_of_voxels());
+    /// assert!(vox.models[0].is_voxel_at_pos(7, 1, 0));
+    /// assert!(vox.models[0].is_voxel_at_pos(6, 0, 1));
+    /// ```
+    pub fn mirror_copy(&mut self, axis: Axis) -> Result<(), VoxError> {
+        let mut size = [self.size.0, self.size.1, self.size.2];
+        let doubled = size[axis.index()] as u32 * 2;
+        if doubled > 256 {
+            return Err(VoxError::SizeTooLarge {
+                axis,
+                value: doubled,
+            });
+        }
+        size[axis.index()] = doubled as u16;
+
+        let original = doubled / 2;
+        let mirrored: Vec<Voxel> = self
+            .voxels
+            .iter()
+            .filter_map(|voxel| {
+                let mut position = [voxel.position.0, voxel.position.1, voxel.position.2];
+                if position[axis.index()] as u32 >= original {
+                    return None;
+                }
+                position[axis.index()] = (doubled - 1 - position[axis.index()] as u32) as u8;
+                Some(Voxel::new(
+                    position[0],
+                    position[1],
+                    position[2],
+                    voxel.color_index,
+                ))
+            })
+            .collect();
+
+        self.size = (size[0], size[1], size[2]);
+        for voxel in mirrored {
+            self.push_voxel(voxel);
+        }
+        Ok(())
+    }
+
+    /// Returns how many different color indexes are used by the voxels in the model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 7).unwrap();
+    /// vox.models[0].add_voxel_at_pos(3, 1, 1, 3).unwrap();
+    /// assert_eq!(2, vox.models[0].distinct_color_count());
+    /// ```
+    pub fn distinct_color_count(&self) -> usize {
+        let mut used = [false; 256];
+        for voxel in self.voxels.iter() {
+            used[voxel.color_index as usize] = true;
+        }
+        used.iter().filter(|used| **used).count()
+    }
+
+    /// Counts how many voxels have each color index. The count for a color index is at that index of the
+    /// array.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 7).unwrap();
+    /// vox.models[0].add_voxel_at_pos(3, 1, 1, 3).unwrap();
+    ///
+    /// let histogram = vox.models[0].color_histogram();
+    /// assert_eq!(2, histogram[3]);
+    /// assert_eq!(1, histogram[7]);
+    /// assert_eq!(0, histogram[4]);
+    /// ```
+    pub fn color_histogram(&self) -> [u32; 256] {
+        let mut histogram = [0; 256];
+        for voxel in self.voxels.iter() {
+            histogram[voxel.color_index as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Gets the color indexes that are used by at least one voxel, from smallest to largest.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 7).unwrap();
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 3).unwrap();
+    /// vox.models[0].add_voxel_at_pos(3, 1, 1, 7).unwrap();
+    ///
+    /// assert_eq!(vec![3, 7], vox.models[0].used_color_indices());
+    /// ```
+    pub fn used_color_indices(&self) -> Vec<u8> {
+        let histogram = self.color_histogram();
+        (0..=255u8)
+            .filter(|index| histogram[*index as usize] > 0)
+            .collect()
+    }
+
+    /// Gets the smallest and largest color index used by the voxels, or None if there are no voxels. This
+    /// can be used to check if a model only uses colors in one part of the palette.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// assert_eq!(None, vox.models[0].color_index_range());
+    ///
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 40).unwrap();
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 17).unwrap();
+    /// assert_eq!(Some((17, 40)), vox.models[0].color_index_range());
+    /// ```
+    pub fn color_index_range(&self) -> Option<(u8, u8)> {
+        let min = self.voxels.iter().map(|voxel| voxel.color_index).min()?;
+        let max = self.voxels.iter().map(|voxel| voxel.color_index).max()?;
+        Some((min, max))
+    }
+
+    /// Grows the two smaller sides of the model to be the same as the largest side, so the model is a cube.
+    /// The voxels are moved to stay in the center of the model. It will return an error and leave the model
+    /// as it is if the largest side is greater than 256.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(4,2,6);
+    /// vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(3, 1, 5, 1).unwrap();
+    ///
+    /// vox.models[0].pad_to_cube().unwrap();
+    /// assert_eq!((6, 6, 6), vox.models[0].size);
+    /// assert!(vox.models[0].is_voxel_at_pos(1, 2, 0));
+    /// assert!(vox.models[0].is_voxel_at_pos(4, 3, 5));
+    /// ```
+    pub fn pad_to_cube(&mut self) -> Result<(), VoxError> {
+        let size = [self.size.0, self.size.1, self.size.2];
+        let largest = self.size.0.max(self.size.1).max(self.size.2);
+        if largest > 256 {
+            let axis =
+                [Axis::X, Axis::Y, Axis::Z][size.iter().position(|side| *side == largest).unwrap()];
+            return Err(VoxError::SizeTooLarge {
+                axis,
+                value: largest as u32,
+            });
+        }
+
+        let offset = |side: u16| ((largest - side) / 2) as u8;
+        let offset = (offset(size[0]), offset(size[1]), offset(size[2]));
+        for voxel in self.voxels.iter_mut() {
+            voxel.position.0 += offset.0;
+            voxel.position.1 += offset.1;
+            voxel.position.2 += offset.2;
+        }
+        self.size = (largest, largest, largest);
+        self.voxels_changed();
+        Ok(())
+    }
+
+    /// Returns the volume of the model from its size, the number of voxels, and how much of the volume is
+    /// filled from 0.0 to 1.0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(4,4,4);
+    /// vox.models[0].add_cube(0, 0, 0, 4, 4, 2, 1).unwrap();
+    /// assert_eq!((64, 32, 0.5), vox.models[0].metrics());
+    /// ```
+    pub fn metrics(&self) -> (u64, u64, f32) {
+        let volume = self.size.0 as u64 * self.size.1 as u64 * self.size.2 as u64;
+        let voxels = self.voxels.len() as u64;
+        let fill = if volume == 0 {
+            0.0
+        } else {
+            voxels as f32 / volume as f32
+        };
+        (volume, voxels, fill)
+    }
+
+    //centers of the voxels that have an empty side, including sides on the edge of the model
+    fn surface_centers(&self, occupancy: &OccupancyMap) -> Vec<(f32, f32, f32)> {
+        let mut centers = Vec::new();
+        for voxel in self.voxels.iter() {
+            let (x, y, z) = voxel.position;
+            let open = |dx: i16, dy: i16, dz: i16| {
+                let neighbor = (x as i16 + dx, y as i16 + dy, z as i16 + dz);
+                neighbor.0 < 0
+                    || neighbor.1 < 0
+                    || neighbor.2 < 0
+                    || neighbor.0 > 255
+                    || neighbor.1 > 255
+                    || neighbor.2 > 255
+                    || !occupancy.contains((neighbor.0 as u8, neighbor.1 as u8, neighbor.2 as u8))
+            };
+            if open(1, 0, 0)
+                || open(-1, 0, 0)
+                || open(0, 1, 0)
+                || open(0, -1, 0)
+                || open(0, 0, 1)
+                || open(0, 0, -1)
+            {
+                centers.push((x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5));
+            }
+        }
+        centers
+    }
+
+    //distance to the closest surface center, negative when the point is inside of a voxel
+    fn distance_to_surface(
+        occupancy: &OccupancyMap,
+        surface: &[(f32, f32, f32)],
+        point: (f32, f32, f32),
+    ) -> f32 {
+        let mut closest = f32::INFINITY;
+        for center in surface.iter() {
+            let dx = point.0 - center.0;
+            let dy = point.1 - center.1;
+            let dz = point.2 - center.2;
+            closest = closest.min((dx * dx + dy * dy + dz * dz).sqrt());
+        }
+
+        let inside = point.0 >= 0.0
+            && point.1 >= 0.0
+            && point.2 >= 0.0
+            && point.0 < 256.0
+            && point.1 < 256.0
+            && point.2 < 256.0
+            && occupancy.contains((point.0 as u8, point.1 as u8, point.2 as u8));
+        if inside {
+            -closest
+        } else {
+            closest
+        }
+    }
+
+    /// Gets about how far the point is from the surface of the model, measured to the centers of the voxels
+    /// on the surface. The center of the voxel at (x, y, z) is at (x + 0.5, y + 0.5, z + 0.5). The distance
+    /// is negative when the point is inside of a voxel, and infinite when the model is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(21,21,21);
+    /// for x in 0..21 {
+    ///     for y in 0..21 {
+    ///         for z in 0..21 {
+    ///             let (dx, dy, dz) = (x as f32 - 10.0, y as f32 - 10.0, z as f32 - 10.0);
+    ///             if (dx * dx + dy * dy + dz * dz).sqrt() <= 10.0 {
+    ///                 vox.models[0].add_voxel_at_pos(x, y, z, 1).unwrap();
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let center = vox.models[0].signed_distance(10.5, 10.5, 10.5);
+    /// assert!(center < -9.0 && center > -11.0);
+    /// assert!(vox.models[0].signed_distance(0.5, 0.5, 0.5) > 0.0);
+    /// ```
+    pub fn signed_distance(&self, x: f32, y: f32, z: f32) -> f32 {
+        let occupancy = self.occupancy_map();
+        let surface = self.surface_centers(&occupancy);
+        Model::distance_to_surface(&occupancy, &surface, (x, y, z))
+    }
+
+    /// Like signed_distance() but gets the distance at the center of every position in the model. The
+    /// distance for (x, y, z) is at index `x + y * size.x + z * size.x * size.y`.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(3,1,1);
+    /// vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    ///
+    /// let field = vox.models[0].distance_field();
+    /// assert_eq!(vec![-0.0, 1.0, 2.0], field);
+    /// ```
+    pub fn distance_field(&self) -> Vec<f32> {
+        let occupancy = self.occupancy_map();
+        let surface = self.surface_centers(&occupancy);
+
+        let mut field =
+            Vec::with_capacity(self.size.0 as usize * self.size.1 as usize * self.size.2 as usize);
+        for z in 0..self.size.2 {
+            for y in 0..self.size.1 {
+                for x in 0..self.size.0 {
+                    field.push(Model::distance_to_surface(
+                        &occupancy,
+                        &surface,
+                        (x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5),
+                    ));
+                }
+            }
+        }
+        field
+    }
+
+    /// Adds the voxels of another model to this model, with the corner of the other model at the position
+    /// given. When a voxel is added where there already is one, on_conflict is called with the color index
+    /// already there and the color index being added, and the voxel gets the color index it returns. If it
+    /// returns 0 the voxel is removed, since index 0 means there is no voxel. It will return an error and
+    /// leave the model as it is if any of the voxels would be outside of this model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(2, 2, 2, 4).unwrap();
+    /// vox.models[0].add_voxel_at_pos(3, 2, 2, 9).unwrap();
+    ///
+    /// let mut other = Model::new(2, 1, 1);
+    /// other.add_voxel_at_pos(0, 0, 0, 6).unwrap();
+    /// other.add_voxel_at_pos(1, 0, 0, 6).unwrap();
+    ///
+    /// vox.models[0].merge_with(&other, (2, 2, 2), |existing, added| existing.max(added)).unwrap();
+    /// assert_eq!(2, vox.models[0].num_of_voxels());
+    /// assert_eq!(6, vox.models[0].voxels[0].color_index);
+    /// assert_eq!(9, vox.models[0].voxels[1].color_index);
+    ///
+    /// assert!(vox.models[0].merge_with(&other, (9, 0, 0), |existing, _| existing).is_err());
+    ///
+    /// // 0 removes the voxels that are in both
+    /// vox.models[0].merge_with(&other, (2, 2, 2), |_, _| 0).unwrap();
+    /// assert!(vox.models[0].is_empty());
+    /// ```
+    pub fn merge_with(
+        &mut self,
+        other: &Model,
+        at: (u8, u8, u8),
+        on_conflict: impl Fn(u8, u8) -> u8,
+    ) -> Result<(), VoxError> {
+        let moved = |voxel: &Voxel| {
+            (
+                voxel.position.0 as u16 + at.0 as u16,
+                voxel.position.1 as u16 + at.1 as u16,
+                voxel.position.2 as u16 + at.2 as u16,
+            )
+        };
+        for voxel in other.voxels.iter() {
+            let position = moved(voxel);
+            if position.0 >= self.size.0 || position.1 >= self.size.1 || position.2 >= self.size.2 {
+                //positions past 255 can not be shown, so they are shown as 255
+                return Err(VoxError::VoxelOutOfBounds {
+                    pos: (
+                        position.0.min(255) as u8,
+                        position.1.min(255) as u8,
+                        position.2.min(255) as u8,
+                    ),
+                    size: self.size,
+                });
+            }
+        }
+
+        let mut occupancy = self.occupancy_map();
+        for voxel in other.voxels.iter() {
+            let position = moved(voxel);
+            let position = (position.0 as u8, position.1 as u8, position.2 as u8);
+            match occupancy.index_of(position) {
+                //a voxel removed by on_conflict is empty until it is taken out after the loop
+                Some(index) if self.voxels[index].color_index == 0 => {
+                    self.voxels[index].color_index = voxel.color_index;
+                }
+                Some(index) => {
+                    let existing = self.voxels[index].color_index;
+                    self.voxels[index].color_index = on_conflict(existing, voxel.color_index);
+                }
+                None => {
+                    occupancy.insert(position, self.voxels.len());
+                    self.push_voxel(Voxel {
+                        position,
+                        color_index: voxel.color_index,
+                    });
+                }
+            }
+        }
+        if self.voxels.iter().any(|voxel| voxel.color_index == 0) {
+            self.retain_voxels(|voxel| voxel.color_index != 0);
+        }
+        Ok(())
+    }
+
+    /// Gets the voxels sorted by z, then y, then x, which is the order they are stored in when saving with
+    /// save_sorted(). Voxels at the same position are sorted by color index.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(5, 0, 1, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(9, 9, 0, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(1, 0, 1, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(0, 2, 0, 1).unwrap();
+    ///
+    /// let positions: Vec<(u8, u8, u8)> = vox.models[0]
+    ///     .sorted_by_zyx()
+    ///     .iter()
+    ///     .map(|voxel| voxel.position)
+    ///     .collect();
+    /// assert_eq!(vec![(0, 2, 0), (9, 9, 0), (1, 0, 1), (5, 0, 1)], positions);
+    /// ```
+    pub fn sorted_by_zyx(&self) -> Vec<&Voxel> {
+        let mut voxels: Vec<&Voxel> = self.voxels.iter().collect();
+        voxels.sort_by_key(|voxel| {
+            (
+                voxel.position.2,
+                voxel.position.1,
+                voxel.position.0,
+                voxel.color_index,
+            )
+        });
+        voxels
+    }
+
+    /// Fills in each z slice of the model inside of the convex hull of the voxels in that slice, so a few
+    /// voxels marking the outline of a shape become a solid shape. New voxels use the color index given and
+    /// voxels already in the model are not changed. It will return an error if colorindex is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 0, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(5, 1, 0, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(1, 5, 0, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(5, 5, 0, 1).unwrap();
+    ///
+    /// vox.models[0].fill_convex_hull(2).unwrap();
+    /// assert_eq!(25, vox.models[0].num_of_voxels());
+    /// assert!(vox.models[0].is_voxel_at_pos(3, 3, 0));
+    /// assert!(!vox.models[0].is_voxel_at_pos(3, 3, 1));
+    /// ```
+    pub fn fill_convex_hull(&mut self, colorindex: u8) -> Result<(), VoxError> {
+        check_color(colorindex)?;
+        let mut slices: Vec<Vec<(i32, i32)>> = vec![Vec::new(); 256];
+        for voxel in self.voxels.iter() {
+            slices[voxel.position.2 as usize]
+                .push((voxel.position.0 as i32, voxel.position.1 as i32));
+        }
+
+        let occupancy = self.occupancy_map();
+        for (z, points) in slices.iter_mut().enumerate() {
+            if points.is_empty() {
+                continue;
+            }
+            let hull = convex_hull(points);
+
+            let min_x = hull.iter().map(|point| point.0).min().unwrap();
+            let max_x = hull.iter().map(|point| point.0).max().unwrap();
+            let min_y = hull.iter().map(|point| point.1).min().unwrap();
+            let max_y = hull.iter().map(|point| point.1).max().unwrap();
+            for x in min_x..=max_x {
+                for y in min_y..=max_y {
+                    let position = (x as u8, y as u8, z as u8);
+                    if in_convex_hull(&hull, (x, y)) && !occupancy.contains(position) {
+                        self.push_voxel(Voxel {
+                            position,
+                            color_index: colorindex,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes a new model with the same size that only has the voxels with the color index given. This model
+    /// is not changed.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(0, 0, 0, 2, 2, 2, 3).unwrap();
+    /// vox.models[0].add_voxel_at_pos(5, 5, 5, 4).unwrap();
+    ///
+    /// let glass = vox.models[0].extract_color(3);
+    /// assert_eq!((10, 10, 10), glass.size);
+    /// assert_eq!(8, glass.num_of_voxels());
+    /// assert!(glass.voxels.iter().all(|voxel| voxel.color_index == 3));
+    /// assert_eq!(9, vox.models[0].num_of_voxels());
+    /// ```
+    pub fn extract_color(&self, index: u8) -> Model {
+        let mut model = Model::new(self.size.0, self.size.1, self.size.2);
+        model.voxels = self
+            .voxels
+            .iter()
+            .filter(|voxel| voxel.color_index == index)
+            .cloned()
+            .collect();
+        model
+    }
+
+    /// Like extract_color() but makes a model for every color index used, with the color index as the key.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 4).unwrap();
+    /// vox.models[0].add_voxel_at_pos(3, 1, 1, 3).unwrap();
+    ///
+    /// let models = vox.models[0].split_by_color();
+    /// assert_eq!(2, models.len());
+    /// assert_eq!(2, models[&3].num_of_voxels());
+    /// assert_eq!(1, models[&4].num_of_voxels());
+    /// ```
+    pub fn split_by_color(&self) -> HashMap<u8, Model> {
+        let mut models: HashMap<u8, Model> = HashMap::new();
+        for voxel in self.voxels.iter() {
+            models
+                .entry(voxel.color_index)
+                .or_insert_with(|| Model::new(self.size.0, self.size.1, self.size.2))
+                .voxels
+                .push(voxel.clone());
+        }
+        models
+    }
+
+    /// Gets the positions of the voxels connected to the voxel at start that have the same color index,
+    /// including start. Voxels are only connected through their faces. Nothing is returned if there is no
+    /// voxel at start.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(0, 0, 0, 3, 1, 1, 2).unwrap();
+    /// //touches the corner of the line but not a face
+    /// vox.models[0].add_voxel_at_pos(3, 1, 0, 2).unwrap();
+    ///
+    /// let region = vox.models[0].same_color_region((0, 0, 0));
+    /// assert_eq!(3, region.len());
+    /// assert!(!region.contains(&(3, 1, 0)));
+    /// ```
+    pub fn same_color_region(&self, start: (u8, u8, u8)) -> Vec<(u8, u8, u8)> {
+        let occupancy = self.occupancy_map();
+        let color = match occupancy.index_of(start) {
+            Some(index) => self.voxels[index].color_index,
+            None => return Vec::new(),
+        };
+
+        let mut visited = HashSet::new();
+        visited.insert(start);
+        let mut region = vec![start];
+        let mut next = 0;
+        while next < region.len() {
+            let (x, y, z) = region[next];
+            next += 1;
+
+            let neighbors = [
+                x.checked_sub(1).map(|x| (x, y, z)),
+                x.checked_add(1).map(|x| (x, y, z)),
+                y.checked_sub(1).map(|y| (x, y, z)),
+                y.checked_add(1).map(|y| (x, y, z)),
+                z.checked_sub(1).map(|z| (x, y, z)),
+                z.checked_add(1).map(|z| (x, y, z)),
+            ];
+            for neighbor in neighbors.iter().flatten() {
+                let same_color = occupancy
+                    .index_of(*neighbor)
+                    .map(|index| self.voxels[index].color_index)
+                    == Some(color);
+                if same_color && visited.insert(*neighbor) {
+                    region.push(*neighbor);
+                }
+            }
+        }
+        region
+    }
+
+    /// Draws one slice of the model through index on axis as text, with # for a voxel and a space for an
+    /// empty position. Each line is a row of the slice ending with a newline, with the highest row first.
+    /// Slices across X or Y have Z going up, and slices across Z have Y going up.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(4, 3, 1);
+    /// vox.models[0].add_cube(0, 0, 0, 2, 2, 1, 1).unwrap();
+    ///
+    /// assert_eq!("    \n##  \n##  \n", vox.models[0].ascii_slice(Axis::Z, 0));
+    /// ```
+    pub fn ascii_slice(&self, axis: Axis, index: u8) -> String {
+        let size = [self.size.0, self.size.1, self.size.2];
+        let (column_axis, row_axis) = match axis {
+            Axis::X => (1, 2),
+            Axis::Y => (0, 2),
+            Axis::Z => (0, 1),
+        };
+
+        let occupancy = self.occupancy_map();
+        let mut slice = String::new();
+        for row in (0..size[row_axis]).rev() {
+            for column in 0..size[column_axis] {
+                let mut position = [index; 3];
+                position[column_axis] = column as u8;
+                position[row_axis] = row as u8;
+                slice.push(
+                    if occupancy.contains((position[0], position[1], position[2])) {
+                        '#'
+                    } else {
+                        ' '
+                    },
+                );
+            }
+            slice.push('\n');
+        }
+        slice
+    }
+
+    /// Fills the box between 2 corners like add_cube() does, but the color index changes from
+    /// start_color on the min side to end_color on the max side along the axis. Both corners are included.
+    /// Color index 0 can not be used because it means there is no voxel.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// //a sky that goes from index 10 at the bottom to 20 at the top
+    /// vox.models[0].add_cube_gradient((0, 0, 0), (9, 9, 9), Axis::Z, 10, 20).unwrap();
+    ///
+    /// assert_eq!(10, vox.models[0].get_voxel_at(0, 0, 0).unwrap().color_index);
+    /// assert_eq!(20, vox.models[0].get_voxel_at(0, 0, 9).unwrap().color_index);
+    /// ```
+    pub fn add_cube_gradient(
+        &mut self,
+        min: (u8, u8, u8),
+        max: (u8, u8, u8),
+        axis: Axis,
+        start_color: u8,
+        end_color: u8,
+    ) -> Result<(), VoxError> {
+        self.check_in_bounds(max.0, max.1, max.2)?;
+        check_range(min, max)?;
+        for color in [start_color, end_color].iter() {
+            if *color == 0 {
+                return Err(VoxError::InvalidColorIndex(0));
+            }
+        }
+
+        let low = [min.0, min.1, min.2][axis.index()];
+        let high = [max.0, max.1, max.2][axis.index()];
+        for currentx in min.0..=max.0 {
+            for currenty in min.1..=max.1 {
+                for currentz in min.2..=max.2 {
+                    let along = [currentx, currenty, currentz][axis.index()];
+                    let fraction = if high == low {
+                        0.0
+                    } else {
+                        (along - low) as f32 / (high - low) as f32
+                    };
+                    let color =
+                        start_color as f32 + (end_color as f32 - start_color as f32) * fraction;
+                    self.push_voxel(Voxel::new(
+                        currentx,
+                        currenty,
+                        currentz,
+                        color.round() as u8,
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the color index of every voxel to what the closure returns for the position of the voxel.
+    /// Voxels that the closure returns 0 for are removed, because index 0 means there is no voxel.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(0, 0, 0, 4, 1, 1, 1).unwrap();
+    ///
+    /// //stripes that are 2 voxels wide
+    /// vox.models[0].color_by_position(|x, _, _| (x / 2) % 2 + 1);
+    /// assert_eq!(1, vox.models[0].get_voxel_at(1, 0, 0).unwrap().color_index);
+    /// assert_eq!(2, vox.models[0].get_voxel_at(2, 0, 0).unwrap().color_index);
+    /// ```
+    pub fn color_by_position(&mut self, f: impl Fn(u8, u8, u8) -> u8) {
+        for voxel in self.voxels.iter_mut() {
+            let (x, y, z) = voxel.position;
+            voxel.color_index = f(x, y, z);
+        }
+        if self.voxels.iter().any(|voxel| voxel.color_index == 0) {
+            self.retain_voxels(|voxel| voxel.color_index != 0);
+        }
+    }
+
+    /// Adds a line of voxels from one position to another, including both ends. There is a voxel at every
+    /// step along the axis the line goes furthest on, so the line has no gaps. It will return an error
+    /// before adding any voxels if either end is outside of the model or if color_index is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_line((0, 0, 0), (6, 3, 0), 1).unwrap();
+    ///
+    /// assert_eq!(7, vox.models[0].num_of_voxels());
+    /// assert!(vox.models[0].is_voxel_at_pos(6, 3, 0));
+    /// assert!(vox.models[0].add_line((0, 0, 0), (10, 0, 0), 1).is_err());
+    /// ```
+    pub fn add_line(
+        &mut self,
+        from: (u8, u8, u8),
+        to: (u8, u8, u8),
+        color_index: u8,
+    ) -> Result<(), VoxError> {
+        check_color(color_index)?;
+        self.check_in_bounds(from.0, from.1, from.2)?;
+        self.check_in_bounds(to.0, to.1, to.2)?;
+
+        let start = [from.0 as i32, from.1 as i32, from.2 as i32];
+        let difference = [
+            to.0 as i32 - start[0],
+            to.1 as i32 - start[1],
+            to.2 as i32 - start[2],
+        ];
+        let steps = difference.iter().map(|d| d.abs()).max().unwrap();
+        for step in 0..=steps {
+            //rounded to the nearest position, which is exact on the longest axis
+            let along = |axis: usize| {
+                if steps == 0 {
+                    start[axis] as u8
+                } else {
+                    (start[axis] as f32 + difference[axis] as f32 * step as f32 / steps as f32)
+                        .round() as u8
+                }
+            };
+            self.push_voxel(Voxel::new(along(0), along(1), along(2), color_index));
+        }
+
+        Ok(())
+    }
+
+    /// Fills the empty positions that can be reached from start without going through a voxel or outside
+    /// of the model, and returns how many voxels were added. Positions are only connected through their
+    /// faces. Nothing is filled if start has a voxel, is outside of the model, or the color index is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(3,3,3);
+    /// //a wall that splits the model in 2
+    /// vox.models[0].add_cube(1, 0, 0, 2, 3, 3, 1).unwrap();
+    ///
+    /// assert_eq!(9, vox.models[0].fill_empty_from((0, 0, 0), 2));
+    /// assert_eq!(false, vox.models[0].is_voxel_at_pos(2, 0, 0));
+    /// ```
+    pub fn fill_empty_from(&mut self, start: (u8, u8, u8), color: u8) -> u32 {
+        if color == 0 || self.check_in_bounds(start.0, start.1, start.2).is_err() {
+            return 0;
+        }
+        let occupancy = self.occupancy_map();
+        if occupancy.contains(start) {
+            return 0;
+        }
+
+        let mut visited = HashSet::new();
+        visited.insert(start);
+        let mut filled = vec![start];
+        let mut next = 0;
+        while next < filled.len() {
+            let (x, y, z) = filled[next];
+            next += 1;
+
+            let neighbors = [
+                x.checked_sub(1).map(|x| (x, y, z)),
+                x.checked_add(1).map(|x| (x, y, z)),
+                y.checked_sub(1).map(|y| (x, y, z)),
+                y.checked_add(1).map(|y| (x, y, z)),
+                z.checked_sub(1).map(|z| (x, y, z)),
+                z.checked_add(1).map(|z| (x, y, z)),
+            ];
+            for &(x, y, z) in neighbors.iter().flatten() {
+                if self.check_in_bounds(x, y, z).is_ok()
+                    && !occupancy.contains((x, y, z))
+                    && visited.insert((x, y, z))
+                {
+                    filled.push((x, y, z));
+                }
+            }
+        }
+
+        for &(x, y, z) in filled.iter() {
+            self.push_voxel(Voxel::new(x, y, z, color));
+        }
+        filled.len() as u32
+    }
+
+    /// Adds a reflected copy of the voxels on the other side of the middle of the model on the axis,
+    /// without changing the size. A voxel at 1 in a model with a size of 10 is copied to 8. Copies that
+    /// would go where there is already a voxel are not added, so voxels in the middle are not doubled.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(5,5,5);
+    /// vox.models[0].add_voxel_at_pos(0, 1, 1, 1).unwrap();
+    /// //in the middle of the model on x
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 2).unwrap();
+    ///
+    /// vox.models[0].mirror(Axis::X);
+    /// assert_eq!(3, vox.models[0].num_of_voxels());
+    /// assert!(vox.models[0].is_voxel_at_pos(4, 1, 1));
+    /// ```
+    pub fn mirror(&mut self, axis: Axis) {
+        let size = [self.size.0, self.size.1, self.size.2][axis.index()];
+        let mut occupancy = self.occupancy_map();
+
+        let mut mirrored = Vec::new();
+        for voxel in self.voxels.iter() {
+            let mut position = [voxel.position.0, voxel.position.1, voxel.position.2];
+            //voxels outside of the model do not have a place on the other side
+            if position[axis.index()] as u16 >= size {
+                continue;
+            }
+            position[axis.index()] = (size - 1 - position[axis.index()] as u16) as u8;
+
+            let position = (position[0], position[1], position[2]);
+            if !occupancy.contains(position) {
+                occupancy.insert(position, 0);
+                mirrored.push(Voxel::new(
+                    position.0,
+                    position.1,
+                    position.2,
+                    voxel.color_index,
+                ));
+            }
+        }
+
+        for voxel in mirrored {
+            self.push_voxel(voxel);
+        }
+    }
+
+    /// Rotates the voxels by 90 degrees around the axis the number of times given, going counter clockwise
+    /// when looking from the positive side of the axis. The size of the model is changed to fit, so the
+    /// sizes of the other 2 axes swap for each turn. Voxels outside of the model are removed.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(4,2,1);
+    /// vox.models[0].add_voxel_at_pos(3, 0, 0, 1).unwrap();
+    ///
+    /// vox.models[0].rotate90(Axis::Z, 1);
+    /// assert_eq!((2, 4, 1), vox.models[0].size);
+    /// assert!(vox.models[0].is_voxel_at_pos(1, 3, 0));
+    /// ```
+    pub fn rotate90(&mut self, axis: Axis, times: u8) {
+        //the 2 axes that turn, where the first turns towards the second
+        let (first, second) = match axis {
+            Axis::X => (1, 2),
+            Axis::Y => (2, 0),
+            Axis::Z => (0, 1),
+        };
+
+        for _ in 0..times % 4 {
+            let mut size = [self.size.0, self.size.1, self.size.2];
+            self.voxels.retain(|voxel| {
+                (voxel.position.0 as u16) < size[0]
+                    && (voxel.position.1 as u16) < size[1]
+                    && (voxel.position.2 as u16) < size[2]
+            });
+            for voxel in self.voxels.iter_mut() {
+                let mut position = [voxel.position.0, voxel.position.1, voxel.position.2];
+                let old_first = position[first];
+                position[first] = (size[second] - 1 - position[second] as u16) as u8;
+                position[second] = old_first;
+                voxel.position = (position[0], position[1], position[2]);
+            }
+            size.swap(first, second);
+            self.size = (size[0], size[1], size[2]);
+        }
+        self.voxels_changed();
+    }
+
+    /// Makes a new model with the size given where each position copies the voxel at the nearest
+    /// position in this model, so the voxels are stretched or shrunk to fill the new size.
+    ///
+    /// # Panics
+    /// Panics if the target size is greater than 256 on any axis, like set_size().
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(2,2,2);
+    /// vox.models[0].add_voxel_at_pos(0, 0, 0, 4).unwrap();
+    ///
+    /// let larger = vox.models[0].resample((4, 4, 4));
+    /// assert_eq!(8, larger.num_of_voxels());
+    /// assert_eq!(4, larger.get_voxel_at(1, 1, 1).unwrap().color_index);
+    /// ```
+    pub fn resample(&self, target: (u16, u16, u16)) -> Model {
+        let mut model = Model::new(0, 0, 0);
+        model.set_size(target.0, target.1, target.2);
+
+        let occupancy = self.occupancy_map();
+        let source = [self.size.0, self.size.1, self.size.2];
+        let target = [target.0, target.1, target.2];
+        //the middle of a new position is used to find the nearest old position
+        let nearest = |axis: usize, position: u16| {
+            ((position as u32 * 2 + 1) * source[axis] as u32 / (target[axis] as u32 * 2)) as u8
+        };
+        for x in 0..target[0] {
+            for y in 0..target[1] {
+                for z in 0..target[2] {
+                    let from = (nearest(0, x), nearest(1, y), nearest(2, z));
+                    if let Some(index) = occupancy.index_of(from) {
+                        model.push_voxel(Voxel::new(
+                            x as u8,
+                            y as u8,
+                            z as u8,
+                            self.voxels[index].color_index,
+                        ));
+                    }
+                }
+            }
+        }
+        model
+    }
+
+    /// Counts the faces of voxels that are not covered by another voxel for each color index. The count
+    /// for a color index is at that index of the array.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 4).unwrap();
+    ///
+    /// let faces = vox.models[0].exposed_faces_by_color();
+    /// assert_eq!(5, faces[3]);
+    /// assert_eq!(5, faces[4]);
+    /// ```
+    pub fn exposed_faces_by_color(&self) -> [u64; 256] {
+        let occupancy = self.occupancy_map();
+        let mut faces = [0; 256];
+        for voxel in self.voxels.iter() {
+            let (x, y, z) = voxel.position;
+            let neighbors = [
+                x.checked_sub(1).map(|x| (x, y, z)),
+                x.checked_add(1).map(|x| (x, y, z)),
+                y.checked_sub(1).map(|y| (x, y, z)),
+                y.checked_add(1).map(|y| (x, y, z)),
+                z.checked_sub(1).map(|z| (x, y, z)),
+                z.checked_add(1).map(|z| (x, y, z)),
+            ];
+            let exposed = neighbors
+                .iter()
+                .filter(|neighbor| match neighbor {
+                    Some(position) => !occupancy.contains(*position),
+                    None => true,
+                })
+                .count();
+            faces[voxel.color_index as usize] += exposed as u64;
+        }
+        faces
+    }
+
+    /// Adds the voxels of another model to this model, moved by the offset. Voxels that would go where
+    /// there already is a voxel are skipped, so the voxels already in this model are kept. It will return
+    /// an error and leave the model as it is if any of the voxels would be outside of this model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_voxel_at_pos(4, 0, 0, 1).unwrap();
+    ///
+    /// let mut part = Model::new(5, 5, 5);
+    /// part.add_voxel_at_pos(4, 0, 0, 2).unwrap();
+    /// part.add_voxel_at_pos(3, 0, 0, 2).unwrap();
+    ///
+    /// vox.models[0].merge(&part, (1, 0, 0)).unwrap();
+    /// assert_eq!(2, vox.models[0].num_of_voxels());
+    /// assert_eq!(1, vox.models[0].get_voxel_at(4, 0, 0).unwrap().color_index);
+    ///
+    /// assert!(vox.models[0].merge(&part, (-5, 0, 0)).is_err());
+    /// ```
+    pub fn merge(&mut self, other: &Model, offset: (i32, i32, i32)) -> Result<(), VoxError> {
+        let mut moved = Vec::with_capacity(other.voxels.len());
+        for voxel in other.voxels.iter() {
+            let position = (
+                voxel.position.0 as i64 + offset.0 as i64,
+                voxel.position.1 as i64 + offset.1 as i64,
+                voxel.position.2 as i64 + offset.2 as i64,
+            );
+            let inside = |value: i64, size: u16| value >= 0 && value < size as i64;
+            if !inside(position.0, self.size.0)
+                || !inside(position.1, self.size.1)
+                || !inside(position.2, self.size.2)
+            {
+                //positions below 0 or past 255 can not be shown, so they are shown as 0 or 255
+                return Err(VoxError::VoxelOutOfBounds {
+                    pos: (
+                        position.0.clamp(0, 255) as u8,
+                        position.1.clamp(0, 255) as u8,
+                        position.2.clamp(0, 255) as u8,
+                    ),
+                    size: self.size,
+                });
+            }
+            moved.push(Voxel::new(
+                position.0 as u8,
+                position.1 as u8,
+                position.2 as u8,
+                voxel.color_index,
+            ));
+        }
+
+        let mut occupancy = self.occupancy_map();
+        for voxel in moved {
+            if !occupancy.contains(voxel.position) {
+                occupancy.insert(voxel.position, self.voxels.len());
+                self.push_voxel(voxel);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets if the model is strict. When it is strict, add_voxel() and add_voxel_at_pos() return an error
+    /// instead of adding a second voxel at a position. Models are not strict when they are made. Checking
+    /// goes through all of the voxels unless build_index() has been called.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{VoxError, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].set_strict(true);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    ///
+    /// let error = vox.models[0].add_voxel_at_pos(1, 1, 1, 2).unwrap_err();
+    /// assert!(matches!(error, VoxError::DuplicatePosition((1, 1, 1))));
+    /// ```
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Gets the voxels that can be seen, which are the voxels with at least one of their 6 neighbors empty
+    /// or on the outside of the model. Voxels that are covered on every side are left out.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(4,4,4);
+    /// vox.models[0].add_cube(0, 0, 0, 4, 4, 4, 1).unwrap();
+    ///
+    /// //the 2x2x2 inside is hidden
+    /// assert_eq!(64 - 8, vox.models[0].surface_voxels().len());
+    /// ```
+    pub fn surface_voxels(&self) -> Vec<&Voxel> {
+        let occupancy = self.occupancy_map();
+        self.voxels
+            .iter()
+            .filter(|voxel| !is_enclosed(&occupancy, self.size, voxel.position))
+            .collect()
+    }
+
+    /// Removes every voxel that has a voxel on all 6 sides, so only the surface is left. Which voxels are
+    /// removed is decided before any are removed, so the voxels under a removed voxel are not removed
+    /// as well. Voxels on the sides of the model are always kept.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(0, 0, 0, 4, 4, 4, 1).unwrap();
+    ///
+    /// vox.models[0].hollow();
+    /// assert_eq!(64 - 8, vox.models[0].num_of_voxels());
+    /// assert!(!vox.models[0].is_voxel_at_pos(1, 1, 1));
+    /// ```
+    pub fn hollow(&mut self) {
+        let occupancy = self.occupancy_map();
+        let size = self.size;
+        let count = self.voxels.len();
+        self.voxels
+            .retain(|voxel| !is_enclosed(&occupancy, size, voxel.position));
+        if self.voxels.len() != count {
+            self.voxels_changed();
+        }
+    }
+
+    /// Gets the radius and height of a cylinder along the axis that contains the centers of all of the
+    /// voxels. The cylinder is around the average position of the voxels on the other 2 axes, and the
+    /// height is the number of positions between the lowest and highest voxel on the axis, which is 255
+    /// at most. Returns None if there are no voxels.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(2, 2, 0, 4, 4, 6, 1).unwrap();
+    ///
+    /// let (radius, height) = vox.models[0].bounding_cylinder(Axis::Z).unwrap();
+    /// assert!((radius - 0.5f32.hypot(0.5)).abs() < 0.001);
+    /// assert_eq!(6, height);
+    /// ```
+    pub fn bounding_cylinder(&self, axis: Axis) -> Option<(f32, u8)> {
+        if self.voxels.is_empty() {
+            return None;
+        }
+        let (first, second) = match axis {
+            Axis::X => (1, 2),
+            Axis::Y => (0, 2),
+            Axis::Z => (0, 1),
+        };
+        let position = |voxel: &Voxel| [voxel.position.0, voxel.position.1, voxel.position.2];
+
+        let count = self.voxels.len() as f64;
+        let mut center = (0.0, 0.0);
+        for voxel in self.voxels.iter() {
+            center.0 += position(voxel)[first] as f64 / count;
+            center.1 += position(voxel)[second] as f64 / count;
+        }
+
+        let mut radius: f64 = 0.0;
+        let mut low = u8::MAX;
+        let mut high = 0;
+        for voxel in self.voxels.iter() {
+            let position = position(voxel);
+            let distance =
+                (position[first] as f64 - center.0).hypot(position[second] as f64 - center.1);
+            radius = radius.max(distance);
+            low = low.min(position[axis.index()]);
+            high = high.max(position[axis.index()]);
+        }
+
+        Some((radius as f32, (high - low).saturating_add(1)))
+    }
+
+    /// Goes through the voxels of the model. `for voxel in &model` does the same.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(0, 0, 0, 2, 2, 2, 3).unwrap();
+    ///
+    /// assert_eq!(8, vox.models[0].iter().filter(|voxel| voxel.color_index == 3).count());
+    /// for voxel in &vox.models[0] {
+    ///     assert!(voxel.position.0 < 2);
+    /// }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &Voxel> {
+        self.voxels.iter()
+    }
+
+    /// Goes through the voxels of the model so they can be changed. Like changing `voxels` directly,
+    /// build_index() needs to be called again after moving voxels if the model has an index.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(0, 0, 0, 2, 2, 2, 3).unwrap();
+    ///
+    /// for voxel in vox.models[0].iter_mut() {
+    ///     voxel.color_index = 5;
+    /// }
+    /// assert!(vox.models[0].iter().all(|voxel| voxel.color_index == 5));
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Voxel> {
+        self.voxels.iter_mut()
+    }
+
+    /// Makes a hash of the size and voxels of the model that does not depend on the order the voxels were
+    /// added in, so models with the same content have the same hash. The hash is FNV-1a of the size and the
+    /// sorted voxels, so it is the same on every platform and build and can be saved.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Model;
+    ///
+    /// let mut first = Model::new(10, 10, 10);
+    /// first.add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    /// first.add_voxel_at_pos(2, 1, 1, 4).unwrap();
+    ///
+    /// let mut second = Model::new(10, 10, 10);
+    /// second.add_voxel_at_pos(2, 1, 1, 4).unwrap();
+    /// second.add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    ///
+    /// assert_eq!(first.content_hash(), second.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let mut voxels: Vec<_> = self
+            .voxels
+            .iter()
+            .map(|voxel| (voxel.position, voxel.color_index))
+            .collect();
+        voxels.sort_unstable();
+
+        let mut bytes = Vec::with_capacity(6 + voxels.len() * 4);
+        for side in [self.size.0, self.size.1, self.size.2].iter() {
+            bytes.extend_from_slice(&side.to_le_bytes());
+        }
+        for ((x, y, z), color_index) in voxels {
+            bytes.extend_from_slice(&[x, y, z, color_index]);
+        }
+        fnv1a(&bytes)
+    }
+
+    /// Moves the voxels with a color index from from_start to from_end (inclusive) so the range starts at
+    /// to_start, keeping their order. Other voxels are not changed, and the palette needs to be changed
+    /// separately. It will return an error if the range is backwards, if index 0 is used, or if the new
+    /// range would go past 255.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 10).unwrap();
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 12).unwrap();
+    ///
+    /// vox.models[0].remap_color_range(10, 12, 100).unwrap();
+    /// assert_eq!(100, vox.models[0].voxels[0].color_index);
+    /// assert_eq!(102, vox.models[0].voxels[1].color_index);
+    /// ```
+    pub fn remap_color_range(
+        &mut self,
+        from_start: u8,
+        from_end: u8,
+        to_start: u8,
+    ) -> Result<(), VoxError> {
+        if from_start > from_end {
+            return Err(VoxError::InvalidRange {
+                start: from_start,
+                end: from_end,
+            });
+        }
+        if from_start == 0 || to_start == 0 {
+            return Err(VoxError::InvalidColorIndex(0));
+        }
+        if to_start as u16 + (from_end - from_start) as u16 > 255 {
+            return Err(VoxError::InvalidColorIndex(to_start));
+        }
+
+        for voxel in self.voxels.iter_mut() {
+            if (from_start..=from_end).contains(&voxel.color_index) {
+                voxel.color_index = voxel.color_index - from_start + to_start;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks if any voxel is on the side of the model on the axis, which is the side at position 0 or the
+    /// side at the size of the model minus one when high is true. A model that tiles needs voxels on the
+    /// sides that meet the next tile.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Axis, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_cube(0, 2, 2, 4, 4, 10, 1).unwrap();
+    ///
+    /// assert!(vox.models[0].touches_face(Axis::X, false));
+    /// assert!(!vox.models[0].touches_face(Axis::X, true));
+    /// assert!(vox.models[0].touches_face(Axis::Z, true));
+    /// ```
+    pub fn touches_face(&self, axis: Axis, high: bool) -> bool {
+        let index = axis.index();
+        let size = [self.size.0, self.size.1, self.size.2][index];
+        let side = if high { size.saturating_sub(1) } else { 0 };
+        self.voxels.iter().any(|voxel| {
+            let position = [voxel.position.0, voxel.position.1, voxel.position.2];
+            position[index] as u16 == side
+        })
+    }
+
+    /// Goes through every empty position in the model and adds a voxel there with a chance of density,
+    /// which is from 0.0 for none to 1.0 for every position. The same seed always adds the same voxels, and
+    /// a position gets the same result for a seed whether or not the positions before it were empty. It will
+    /// return an error if color_index is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10,10,10);
+    /// vox.models[0].add_noise(0.1, 1, 42).unwrap();
+    /// let stars = vox.models[0].num_of_voxels();
+    /// assert!(stars > 50 && stars < 150);
+    /// ```
+    pub fn add_noise(&mut self, density: f32, color_index: u8, seed: u64) -> Result<(), VoxError> {
+        check_color(color_index)?;
+        if self.size.0 == 0 || self.size.1 == 0 || self.size.2 == 0 {
+            return Ok(());
+        }
+        let occupancy = self.occupancy_map();
+        let max = (
+            (self.size.0 - 1).min(255) as u8,
+            (self.size.1 - 1).min(255) as u8,
+            (self.size.2 - 1).min(255) as u8,
+        );
+
+        let mut state = seed;
+        for position in Model::region_coords((0, 0, 0), max) {
+            //a number is used for every position so filled positions do not change the ones after them
+            let chance = (next_random(&mut state) >> 11) as f64 / (1u64 << 53) as f64;
+            if chance < density as f64 && !occupancy.contains(position) {
+                self.push_voxel(Voxel::new(position.0, position.1, position.2, color_index));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//index 0 means there is no voxel, so it can not be added
+fn check_color(color_index: u8) -> Result<(), VoxError> {
+    if color_index == 0 {
+        return Err(VoxError::InvalidColorIndex(0));
+    }
+    Ok(())
+}
+
+//returns an error for the first axis where the start is after the end
+fn check_range(start: (u8, u8, u8), end: (u8, u8, u8)) -> Result<(), VoxError> {
+    for (start, end) in [(start.0, end.0), (start.1, end.1), (start.2, end.2)].iter() {
+        if start > end {
+            return Err(VoxError::InvalidRange {
+                start: *start,
+                end: *end,
+            });
+        }
+    }
+    Ok(())
+}
+
+//if every position next to the voxel has a voxel. voxels on the sides of the model are never enclosed
+fn is_enclosed(occupancy: &OccupancyMap, size: (u16, u16, u16), position: (u8, u8, u8)) -> bool {
+    let (x, y, z) = position;
+    let on_edge = |value: u8, side: u16| value == 0 || value as u16 + 1 >= side;
+    if on_edge(x, size.0) || on_edge(y, size.1) || on_edge(z, size.2) {
+        return false;
+    }
+    [
+        (x - 1, y, z),
+        (x + 1, y, z),
+        (x, y - 1, z),
+        (x, y + 1, z),
+        (x, y, z - 1),
+        (x, y, z + 1),
+    ]
+    .iter()
+    .all(|neighbor| occupancy.contains(*neighbor))
+}
+
+//splitmix64, which is small and gives the same numbers on every platform
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut value = *state;
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+//64 bit FNV-1a, which gives the same hash on every platform and build
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+//z part of the cross product of a to b and a to c, positive when c is to the left of a to b
+fn cross(a: (i32, i32), b: (i32, i32), c: (i32, i32)) -> i32 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+//corners of the convex hull going counter clockwise, made with the monotone chain algorithm
+fn convex_hull(points: &mut Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+    points.sort_unstable();
+    points.dedup();
+    if points.len() < 3 {
+        return points.clone();
+    }
+
+    let mut hull: Vec<(i32, i32)> = Vec::with_capacity(points.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        let ordered: Vec<&(i32, i32)> = if pass == 0 {
+            points.iter().collect()
+        } else {
+            points.iter().rev().collect()
+        };
+        for point in ordered {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], *point) <= 0
+            {
+                hull.pop();
+            }
+            hull.push(*point);
+        }
+        //the last point is the first point of the next half
+        hull.pop();
+    }
+    hull
+}
+
+//edges are counted as inside. hulls with less than 3 corners are a point or a line
+fn in_convex_hull(hull: &[(i32, i32)], point: (i32, i32)) -> bool {
+    if hull.len() < 3 {
+        let first = hull[0];
+        let last = hull[hull.len() - 1];
+        return cross(first, last, point) == 0
+            && point.0 >= first.0.min(last.0)
+            && point.0 <= first.0.max(last.0)
+            && point.1 >= first.1.min(last.1)
+            && point.1 <= first.1.max(last.1);
+    }
+
+    (0..hull.len()).all(|i| cross(hull[i], hull[(i + 1) % hull.len()], point) >= 0)
 }
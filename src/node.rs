@@ -1,14 +1,46 @@
-use crate::riff::{nGRP, nSHP, nTRN, Dict, VoxString};
-use crate::VoxFile;
+use crate::riff::{nGRP, nSHP, nTRN, parse_string, Dict, Rotation, VoxString};
+use crate::{VoxError, VoxFile};
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{self, BufWriter};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum NodeType {
     Transform(Transform),
     Group,
-    //shape with id of model.
-    Shape(i32),
+    //shape with the models it shows. animated shapes have a model for each keyframe.
+    Shape(Vec<ShapeModel>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShapeModel {
+    pub model_id: i32,
+    //keyframe that the model starts being shown at
+    pub frame: Option<i32>,
+}
+
+impl ShapeModel {
+    pub fn new(model_id: i32) -> ShapeModel {
+        ShapeModel {
+            model_id,
+            frame: None,
+        }
+    }
+
+    pub fn to_dict(&self) -> Dict {
+        match self.frame {
+            Some(frame) => Dict {
+                num_of_pairs: 1,
+                pairs: vec![(
+                    VoxString::new(2, String::from("_f")),
+                    VoxString::new(frame.to_string().len() as i32, frame.to_string()),
+                )],
+            },
+            None => Dict {
+                num_of_pairs: 0,
+                pairs: vec![],
+            },
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -62,20 +94,18 @@ impl Node {
 
         num
     }
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
+    pub fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
         match &(*self).node_type {
-            NodeType::Transform(trans) => {
-                nTRN {
-                    node_id: self.id,
-                    node_attributes: self.attributes.to_dict(),
-                    child_node_id: self.children_ids[0],
-                    reserved_id: -1,
-                    layer_id: trans.layer,
-                    num_of_frames: 1,
-                    frame_attributes: trans.to_dict(),
-                }
-                .write(buf_writer);
+            NodeType::Transform(trans) => nTRN {
+                node_id: self.id,
+                node_attributes: self.attributes.to_dict(),
+                child_node_id: self.children_ids[0],
+                reserved_id: -1,
+                layer_id: trans.layer,
+                num_of_frames: trans.frames.len() as i32,
+                frame_attributes: trans.to_dicts(),
             }
+            .write(buf_writer),
 
             NodeType::Group => nGRP {
                 node_id: self.id,
@@ -85,31 +115,32 @@ impl Node {
             }
             .write(buf_writer),
 
-            NodeType::Shape(model_id) => nSHP {
+            NodeType::Shape(models) => nSHP {
                 node_id: self.id,
                 node_attributes: self.attributes.to_dict(),
-                num_of_models: 1,
-                model_id: *model_id,
-                model_attributes: Dict {
-                    num_of_pairs: 0,
-                    pairs: vec![],
-                },
+                num_of_models: models.len() as i32,
+                models: models
+                    .iter()
+                    .map(|model| (model.model_id, model.to_dict()))
+                    .collect(),
             }
             .write(buf_writer),
         }
     }
 
-    pub fn write_children(&self, buf_writer: &mut BufWriter<File>) {
+    pub fn write_children(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
         for child in self.children.iter() {
-            child.write(buf_writer);
-            child.write_children(buf_writer);
+            child.write(buf_writer)?;
+            child.write_children(buf_writer)?;
         }
+
+        Ok(())
     }
 
-    pub fn write_all(&mut self, buf_writer: &mut BufWriter<File>) {
+    pub fn write_all(&mut self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
         self.number_nodes(0);
         self.number_children_ids();
-        self.write(buf_writer);
+        self.write(buf_writer)?;
         self.write_children(buf_writer)
     }
 
@@ -121,8 +152,8 @@ impl Node {
                 child_node_id: 0,
                 reserved_id: 0,
                 layer_id: -1,
-                num_of_frames: 1,
-                frame_attributes: trans.to_dict(),
+                num_of_frames: trans.frames.len() as i32,
+                frame_attributes: trans.to_dicts(),
             }
             .get_size(),
 
@@ -134,15 +165,14 @@ impl Node {
             }
             .get_size(),
 
-            NodeType::Shape(model_id) => nSHP {
+            NodeType::Shape(models) => nSHP {
                 node_id: 0,
                 node_attributes: self.attributes.to_dict(),
-                num_of_models: 1,
-                model_id: *model_id,
-                model_attributes: Dict {
-                    num_of_pairs: 0,
-                    pairs: vec![],
-                },
+                num_of_models: models.len() as i32,
+                models: models
+                    .iter()
+                    .map(|model| (model.model_id, model.to_dict()))
+                    .collect(),
             }
             .get_size(),
         }
@@ -172,37 +202,117 @@ impl Node {
     }
 
     pub fn get_child_data_to_models(&self, voxfile: &mut VoxFile, used_ids: &mut Vec<i32>) {
-        self.make_model_data(voxfile, used_ids);
+        self.apply_to_models(voxfile, used_ids, SceneTransform::default());
+    }
+
+    //the transforms of the nodes above are added to the transform of each model so models inside of
+    //groups end up where they are in the scene
+    fn apply_to_models(
+        &self,
+        voxfile: &mut VoxFile,
+        used_ids: &mut Vec<i32>,
+        parent: SceneTransform,
+    ) {
+        let scene = match &self.node_type {
+            NodeType::Transform(trans) => {
+                parent.then(&trans.frames.first().cloned().unwrap_or_default())
+            }
+            _ => parent,
+        };
+        self.make_model_data(voxfile, used_ids, scene);
         for child in self.children.iter() {
-            child.get_child_data_to_models(voxfile, used_ids);
+            child.apply_to_models(voxfile, used_ids, scene);
         }
     }
 
-    pub fn make_model_data(&self, voxfile: &mut VoxFile, used_ids: &mut Vec<i32>) {
-        let data = VoxFile::check_transform(self);
-        if data.is_some() {
-            if used_ids.contains(&data.as_ref().unwrap().0) {
-                voxfile.add_copy(
-                    data.as_ref().unwrap().0,
-                    data.as_ref().unwrap().1,
-                    data.as_ref().unwrap().2,
-                    data.as_ref().unwrap().3,
-                    data.as_ref().unwrap().4.clone(),
-                );
-            } else {
-                voxfile.change_model_data(
-                    data.as_ref().unwrap().0,
-                    data.as_ref().unwrap().1,
-                    data.as_ref().unwrap().2,
-                    data.as_ref().unwrap().3,
-                    data.as_ref().unwrap().4.clone(),
-                );
-                used_ids.push(data.as_ref().unwrap().0);
+    fn make_model_data(
+        &self,
+        voxfile: &mut VoxFile,
+        used_ids: &mut Vec<i32>,
+        scene: SceneTransform,
+    ) {
+        let (id, _, layer, _, name) = match VoxFile::check_transform(self) {
+            Some(data) => data,
+            None => return,
+        };
+        let rotation = scene.rotation_byte();
+        if used_ids.contains(&id) {
+            voxfile.add_copy(id, scene.translation, layer, rotation, name);
+        } else {
+            voxfile.change_model_data(id, scene.translation, layer, rotation, name);
+            used_ids.push(id);
+
+            if let NodeType::Shape(models) = &self.children[0].node_type {
+                if models.len() > 1 {
+                    voxfile.change_model_frames(models[0].model_id, models.clone());
+                }
             }
         }
     }
 }
 
+//the translation and rotation of a node in the scene. they are None when no transform above the node has
+//one, so models that are not moved do not get a position
+#[derive(Clone, Copy, Default)]
+struct SceneTransform {
+    translation: Option<(i32, i32, i32)>,
+    rotation: Option<[[i8; 3]; 3]>,
+}
+
+impl SceneTransform {
+    //the transform of a child with the frame, which is moved and rotated by this transform
+    fn then(&self, frame: &Frame) -> SceneTransform {
+        let parent_rotation = self.rotation.unwrap_or(Rotation::IDENTITY.to_matrix());
+        let rotation = frame
+            .rotation
+            .and_then(|rotation| Rotation::from_byte(rotation as u8))
+            .map(|rotation| rotation.to_matrix());
+
+        let translation = match (self.translation, frame.translation) {
+            (None, None) => None,
+            (parent, local) => {
+                let (px, py, pz) = parent.unwrap_or((0, 0, 0));
+                let local = local.unwrap_or((0, 0, 0));
+                let rotated = rotate(parent_rotation, [local.0, local.1, local.2]);
+                Some((px + rotated[0], py + rotated[1], pz + rotated[2]))
+            }
+        };
+        let rotation = match (self.rotation, rotation) {
+            (None, None) => None,
+            (_, local) => {
+                let local = local.unwrap_or(Rotation::IDENTITY.to_matrix());
+                let mut combined = [[0; 3]; 3];
+                for (row, entries) in combined.iter_mut().enumerate() {
+                    for (column, entry) in entries.iter_mut().enumerate() {
+                        *entry = (0..3)
+                            .map(|i| parent_rotation[row][i] * local[i][column])
+                            .sum();
+                    }
+                }
+                Some(combined)
+            }
+        };
+        SceneTransform {
+            translation,
+            rotation,
+        }
+    }
+
+    fn rotation_byte(&self) -> Option<u8> {
+        self.rotation
+            .and_then(Rotation::from_matrix)
+            .map(|rotation| rotation.to_byte())
+    }
+}
+
+fn rotate(matrix: [[i8; 3]; 3], vector: [i32; 3]) -> [i32; 3] {
+    let mut rotated = [0; 3];
+    for (row, value) in rotated.iter_mut().enumerate() {
+        *value = (0..3).map(|i| matrix[row][i] as i32 * vector[i]).sum();
+    }
+    rotated
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct NodeAttributes {
     pub name: Option<String>,
@@ -260,72 +370,86 @@ impl NodeAttributes {
 #[derive(Debug, PartialEq, Clone)]
 pub struct Transform {
     pub layer: i32,
-    //need to make rotation type
-    pub rotation: Option<i32>,
-    pub translation: Option<(i32, i32, i32)>,
+    //a transform has at least one frame, animated transforms have one for each keyframe
+    pub frames: Vec<Frame>,
 }
 
 impl Transform {
-    pub fn to_dict(&self) -> Dict {
-        Dict {
-            num_of_pairs: self.num_of_pairs(),
-            pairs: self.get_pairs(),
-        }
+    pub fn to_dicts(&self) -> Vec<Dict> {
+        self.frames.iter().map(Frame::to_dict).collect()
     }
 
-    pub fn num_of_pairs(&self) -> i32 {
-        if self.rotation.is_some() && self.translation.is_some() {
-            2
-        } else if self.rotation.is_some() || self.translation.is_some() {
-            1
-        } else {
-            0
+    pub fn default() -> Transform {
+        Transform {
+            layer: 0,
+            frames: vec![Frame::default()],
         }
     }
+}
 
-    pub fn get_pairs(&self) -> Vec<(VoxString, VoxString)> {
-        let mut pairs = Vec::new();
-        match self.rotation {
-            Some(rot) => pairs.push((
-                VoxString::new(2, String::from("_r")),
-                //it was here
-                VoxString::new(rot.to_string().len() as i32, rot.to_string()),
-            )),
-            None => {}
-        }
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Frame {
+    //need to make rotation type
+    pub rotation: Option<i32>,
+    pub translation: Option<(i32, i32, i32)>,
+    //keyframe that the frame starts at
+    pub index: Option<i32>,
+}
 
-        match self.translation {
-            Some(_) => pairs.push((
-                VoxString::new(2, String::from("_t")),
-                VoxString::new(
-                    self.translation_to_string().len() as i32,
-                    self.translation_to_string(),
-                ),
-            )),
-            None => {}
+impl Frame {
+    pub fn from_dict(dict: &Dict) -> Result<Frame, VoxError> {
+        let mut frame = Frame::default();
+        for (key, value) in dict.pairs.iter() {
+            //only the known keys are numbers
+            let numbers = |count: usize| {
+                let parsed = parse_string(&value.content)?;
+                if parsed.len() != count {
+                    return Err(VoxError::InvalidFile(format!(
+                        "frame value {:?} for {} needs {} numbers",
+                        value.content, key.content, count
+                    )));
+                }
+                Ok(parsed)
+            };
+            match key.content.as_str() {
+                "_r" => frame.rotation = Some(numbers(1)?[0]),
+                "_t" => {
+                    let parsed = numbers(3)?;
+                    frame.translation = Some((parsed[0], parsed[1], parsed[2]));
+                }
+                "_f" => frame.index = Some(numbers(1)?[0]),
+                _ => {}
+            }
         }
-
-        pairs
+        Ok(frame)
     }
 
-    pub fn translation_to_string(&self) -> String {
-        format!(
-            "{} {} {}",
-            self.translation.unwrap().0,
-            self.translation.unwrap().1,
-            self.translation.unwrap().2
-        )
-    }
+    pub fn to_dict(&self) -> Dict {
+        let mut pairs = Vec::new();
+        if let Some(rotation) = self.rotation {
+            pairs.push(string_pair("_r", rotation.to_string()));
+        }
+        if let Some((x, y, z)) = self.translation {
+            pairs.push(string_pair("_t", format!("{} {} {}", x, y, z)));
+        }
+        if let Some(index) = self.index {
+            pairs.push(string_pair("_f", index.to_string()));
+        }
 
-    pub fn default() -> Transform {
-        Transform {
-            layer: 0,
-            rotation: None,
-            translation: None,
+        Dict {
+            num_of_pairs: pairs.len() as i32,
+            pairs,
         }
     }
 }
 
+fn string_pair(key: &str, value: String) -> (VoxString, VoxString) {
+    (
+        VoxString::new(key.len() as i32, String::from(key)),
+        VoxString::new(value.len() as i32, value),
+    )
+}
+
 pub fn bool_to_string(value: bool) -> String {
     if value {
         String::from("1")
@@ -333,3 +457,98 @@ pub fn bool_to_string(value: bool) -> String {
         String::from("0")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(translation: Option<(i32, i32, i32)>, rotation: Option<i32>, child: Node) -> Node {
+        let mut node = Node::new(
+            NodeType::Transform(Transform {
+                layer: 0,
+                frames: vec![Frame {
+                    rotation,
+                    translation,
+                    index: None,
+                }],
+            }),
+            NodeAttributes::new(),
+        );
+        node.add_child(child);
+        node
+    }
+
+    fn group(child: Node) -> Node {
+        let mut node = Node::new(NodeType::Group, NodeAttributes::new());
+        node.add_child(child);
+        node
+    }
+
+    fn shape(model_id: i32) -> Node {
+        Node::new(
+            NodeType::Shape(vec![ShapeModel::new(model_id)]),
+            NodeAttributes::new(),
+        )
+    }
+
+    #[test]
+    fn nested_group_translations_are_added() {
+        let mut vox = VoxFile::new(2, 2, 2);
+        let model = transform(Some((1, 2, 3)), None, shape(0));
+        let inner = transform(Some((0, 5, 0)), None, group(model));
+        let outer = transform(Some((10, 0, 0)), None, group(inner));
+        vox.root_node = transform(None, None, group(outer));
+
+        vox.get_node_data();
+        assert_eq!(Some((11, 7, 3)), vox.models[0].position);
+        assert_eq!(None, vox.models[0].rotation);
+    }
+
+    #[test]
+    fn group_rotation_turns_children() {
+        let mut vox = VoxFile::new(2, 2, 2);
+        //90 degrees around the z axis
+        let model = transform(Some((10, 0, 0)), Some(17), shape(0));
+        let turned = transform(Some((0, 0, 4)), Some(17), group(model));
+        vox.root_node = transform(None, None, group(turned));
+
+        vox.get_node_data();
+        assert_eq!(Some((0, 10, 4)), vox.models[0].position);
+        //turned twice is 180 degrees around the z axis
+        let rotation = Rotation::from_byte(vox.models[0].rotation.unwrap()).unwrap();
+        assert_eq!([[-1, 0, 0], [0, -1, 0], [0, 0, 1]], rotation.to_matrix());
+    }
+
+    #[test]
+    fn models_without_transforms_stay_unmoved() {
+        let mut vox = VoxFile::new(2, 2, 2);
+        vox.root_node = transform(None, None, group(transform(None, None, shape(0))));
+
+        vox.get_node_data();
+        assert_eq!(None, vox.models[0].position);
+        assert_eq!(None, vox.models[0].rotation);
+    }
+
+    #[test]
+    fn nodes_compare_whole_trees() {
+        let make = |position: (i32, i32, i32)| {
+            let mut vox = VoxFile::new(2, 2, 2);
+            vox.models[0].position = Some(position);
+            vox.add_model_copy(0, 5, 5, 5);
+            vox.make_nodes();
+            vox.root_node
+        };
+
+        assert_eq!(make((1, 2, 3)), make((1, 2, 3)));
+        //only the translation of a node two levels down is different
+        assert_ne!(make((1, 2, 3)), make((1, 2, 4)));
+
+        let mut renamed = make((1, 2, 3));
+        renamed.children[0].children[1].attributes.name = Some(String::from("copy"));
+        assert_ne!(make((1, 2, 3)), renamed);
+
+        let mut fewer = make((1, 2, 3));
+        fewer.children[0].children.pop();
+        assert_ne!(make((1, 2, 3)), fewer);
+    }
+}
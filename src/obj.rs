@@ -0,0 +1,199 @@
+use crate::model::Model;
+use crate::Color;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// faces between two voxels are never seen so only faces next to an empty position are written. faces
+// that are next to each other in the same plane with the same color are joined into one larger quad.
+// positions are written as they are in the model, so z is the up axis.
+
+//a face of a voxel, stored as the axis it faces along, if it faces the positive direction, and the
+//position of the plane it is on
+type Plane = (usize, bool, u16);
+//the color index of a quad and its corners
+type Quad = (u8, [(u16, u16, u16); 4]);
+
+impl Model {
+    /// Makes a Wavefront .obj mesh of the surface of the model. Every face uses the material named
+    /// color_ and the color index of the voxel, which to_obj_mtl() makes. Voxels that have a color with an
+    /// alpha of 0 on the palette are left out.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_cube(0, 0, 0, 2, 2, 2, 1).unwrap();
+    ///
+    /// let obj = vox.models[0].to_obj(&vox.palette);
+    /// //every side of the cube is one quad made of 2 triangles
+    /// assert_eq!(12, obj.lines().filter(|line| line.starts_with("f ")).count());
+    /// assert_eq!(8, obj.lines().filter(|line| line.starts_with("v ")).count());
+    /// ```
+    pub fn to_obj(&self, palette: &[Color; 256]) -> String {
+        let mut vertices: Vec<(u16, u16, u16)> = Vec::new();
+        let mut vertex_ids: HashMap<(u16, u16, u16), usize> = HashMap::new();
+        let mut faces: Vec<(u8, [usize; 4])> = Vec::new();
+        for (color, corners) in self.surface_quads(palette) {
+            let mut ids = [0; 4];
+            for (id, corner) in ids.iter_mut().zip(corners.iter()) {
+                //ids in .obj files start at 1
+                *id = *vertex_ids.entry(*corner).or_insert_with(|| {
+                    vertices.push(*corner);
+                    vertices.len()
+                });
+            }
+            faces.push((color, ids));
+        }
+        faces.sort_by_key(|(color, _)| *color);
+
+        let mut obj = String::new();
+        for (x, y, z) in vertices.iter() {
+            writeln!(obj, "v {} {} {}", x, y, z).unwrap();
+        }
+        let mut current_color = None;
+        for (color, ids) in faces.iter() {
+            if current_color != Some(*color) {
+                writeln!(obj, "usemtl color_{}", color).unwrap();
+                current_color = Some(*color);
+            }
+            writeln!(obj, "f {} {} {}", ids[0], ids[1], ids[2]).unwrap();
+            writeln!(obj, "f {} {} {}", ids[0], ids[2], ids[3]).unwrap();
+        }
+        obj
+    }
+
+    /// Makes the .mtl materials used by to_obj(), with one material for every color index the model uses.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.set_palette_color(3, 255, 0, 0, 255);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    ///
+    /// let mtl = vox.models[0].to_obj_mtl(&vox.palette);
+    /// assert!(mtl.contains("newmtl color_3"));
+    /// assert!(mtl.contains("Kd 1.0000 0.0000 0.0000"));
+    /// ```
+    pub fn to_obj_mtl(&self, palette: &[Color; 256]) -> String {
+        let mut used = [false; 256];
+        for voxel in self.voxels.iter() {
+            used[voxel.color_index as usize] = true;
+        }
+
+        let mut mtl = String::new();
+        for index in 1..=255u8 {
+            let color = palette[index as usize - 1];
+            if !used[index as usize] || color.a == 0 {
+                continue;
+            }
+            writeln!(mtl, "newmtl color_{}", index).unwrap();
+            writeln!(
+                mtl,
+                "Kd {:.4} {:.4} {:.4}",
+                color.r as f32 / 255.0,
+                color.g as f32 / 255.0,
+                color.b as f32 / 255.0
+            )
+            .unwrap();
+            writeln!(mtl, "d {:.4}", color.a as f32 / 255.0).unwrap();
+        }
+        mtl
+    }
+
+    //the corners of every quad on the surface with its color index, in counter clockwise order when
+    //looking at the front of the quad
+    fn surface_quads(&self, palette: &[Color; 256]) -> Vec<Quad> {
+        let visible: Vec<_> = self
+            .voxels
+            .iter()
+            .filter(|voxel| {
+                voxel.color_index != 0 && palette[voxel.color_index as usize - 1].a != 0
+            })
+            .collect();
+        let mut solid = HashMap::new();
+        for voxel in visible.iter() {
+            solid.insert(voxel.position, voxel.color_index);
+        }
+
+        //faces on each plane, with their position along the other 2 axes
+        let mut planes: HashMap<Plane, HashMap<(u16, u16), u8>> = HashMap::new();
+        for (&(x, y, z), &color) in solid.iter() {
+            let position = [x as i16, y as i16, z as i16];
+            for axis in 0..3 {
+                for &positive in [false, true].iter() {
+                    let mut neighbor = position;
+                    neighbor[axis] += if positive { 1 } else { -1 };
+                    let covered = neighbor.iter().all(|value| (0..=255).contains(value))
+                        && solid.contains_key(&(
+                            neighbor[0] as u8,
+                            neighbor[1] as u8,
+                            neighbor[2] as u8,
+                        ));
+                    if covered {
+                        continue;
+                    }
+
+                    let plane = position[axis] as u16 + positive as u16;
+                    let u = position[(axis + 1) % 3] as u16;
+                    let v = position[(axis + 2) % 3] as u16;
+                    planes
+                        .entry((axis, positive, plane))
+                        .or_default()
+                        .insert((u, v), color);
+                }
+            }
+        }
+
+        let mut planes: Vec<_> = planes.into_iter().collect();
+        planes.sort_unstable_by_key(|(plane, _)| *plane);
+        let mut quads = Vec::new();
+        for ((axis, positive, plane), mut faces) in planes {
+            let mut starts: Vec<(u16, u16)> = faces.keys().cloned().collect();
+            starts.sort_unstable_by_key(|&(u, v)| (v, u));
+            for (u, v) in starts {
+                let color = match faces.get(&(u, v)) {
+                    Some(&color) => color,
+                    None => continue,
+                };
+
+                //grows along u first, then adds whole rows along v
+                let mut width = 1;
+                while faces.get(&(u + width, v)) == Some(&color) {
+                    width += 1;
+                }
+                let mut height = 1;
+                while (u..u + width).all(|u| faces.get(&(u, v + height)) == Some(&color)) {
+                    height += 1;
+                }
+                for used_v in v..v + height {
+                    for used_u in u..u + width {
+                        faces.remove(&(used_u, used_v));
+                    }
+                }
+
+                let corner = |u: u16, v: u16| {
+                    let mut position = [0; 3];
+                    position[axis] = plane;
+                    position[(axis + 1) % 3] = u;
+                    position[(axis + 2) % 3] = v;
+                    (position[0], position[1], position[2])
+                };
+                let mut corners = [
+                    corner(u, v),
+                    corner(u + width, v),
+                    corner(u + width, v + height),
+                    corner(u, v + height),
+                ];
+                //the corners go counter clockwise around the positive direction of the axis
+                if !positive {
+                    corners.reverse();
+                }
+                quads.push((color, corners));
+            }
+        }
+        quads
+    }
+}
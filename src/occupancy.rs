@@ -0,0 +1,59 @@
+use crate::Voxel;
+use std::collections::HashMap;
+
+/// The positions of the voxels in a model, made so many positions can be checked without going through
+/// all of the voxels each time. It is not updated when the model changes, so it needs to be made again
+/// after voxels are added or removed. If there is more than one voxel at a position the last one is used.
+///
+/// # Example
+/// ```
+/// use create_vox::VoxFile;
+///
+/// let mut vox = VoxFile::new(10, 10, 10);
+/// vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 1).unwrap();
+///
+/// let occupancy = vox.models[0].occupancy_map();
+/// assert!(occupancy.contains((4, 4, 4)));
+/// assert!(!occupancy.contains((5, 5, 5)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OccupancyMap {
+    //position to index of the voxel in the model
+    positions: HashMap<(u8, u8, u8), usize>,
+}
+
+impl OccupancyMap {
+    pub(crate) fn new(voxels: &[Voxel]) -> OccupancyMap {
+        let mut positions = HashMap::with_capacity(voxels.len());
+        for (index, voxel) in voxels.iter().enumerate() {
+            positions.insert(voxel.position, index);
+        }
+
+        OccupancyMap { positions }
+    }
+
+    //keeps the map up to date when a voxel is pushed onto the model
+    pub(crate) fn insert(&mut self, pos: (u8, u8, u8), index: usize) {
+        self.positions.insert(pos, index);
+    }
+
+    /// Checks if there is a voxel at the position.
+    pub fn contains(&self, pos: (u8, u8, u8)) -> bool {
+        self.positions.contains_key(&pos)
+    }
+
+    /// Gets the index of the voxel at the position in the voxels of the model.
+    pub fn index_of(&self, pos: (u8, u8, u8)) -> Option<usize> {
+        self.positions.get(&pos).copied()
+    }
+
+    /// Returns the number of positions that have a voxel.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if no positions have a voxel.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
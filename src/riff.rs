@@ -1,14 +1,33 @@
 use crate::convert::*;
-use crate::node::{Node, NodeAttributes, NodeType, Transform};
+use crate::node::{Frame, Node, NodeAttributes, NodeType, ShapeModel, Transform};
 use crate::writing::*;
+use crate::VoxError;
 use std::convert::TryInto;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{self, BufWriter};
 
-pub fn write_chunk(name: &str, size: u32, children_size: u32, writer: &mut BufWriter<File>) {
-    write_string_literal(writer, name);
-    write_slice(writer, &u32_to_array(size));
-    write_slice(writer, &u32_to_array(children_size));
+//bytes for the name, size, and children size of a chunk
+pub const CHUNK_HEADER_SIZE: u64 = 12;
+
+//sizes in a chunk header are u32 so anything bigger can not be written
+pub fn chunk_size(size: u64) -> Result<u32, VoxError> {
+    if size > u32::MAX as u64 {
+        return Err(VoxError::TooLarge(size));
+    }
+    Ok(size as u32)
+}
+
+pub fn write_chunk(
+    name: &str,
+    size: u32,
+    children_size: u32,
+    writer: &mut BufWriter<File>,
+) -> io::Result<()> {
+    write_string_literal(writer, name)?;
+    write_slice(writer, &u32_to_array(size))?;
+    write_slice(writer, &u32_to_array(children_size))?;
+
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -18,24 +37,22 @@ pub struct VoxString {
 }
 
 impl VoxString {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> VoxString {
-        let size = i32::from_le_bytes(
-            input[(*cursor as usize)..(4 + *cursor as usize)]
-                .try_into()
-                .expect("failed to read"),
-        );
-        let string = String::from_utf8(
-            input[(4 + *cursor as usize)..((4 + size + *cursor) as usize)].to_vec(),
-        )
-        .unwrap();
+    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Result<VoxString, VoxError> {
+        let size = i32_from_vec(input, cursor)?;
+        let bytes = read_bytes(input, *cursor + 4, size)?;
+        let string = String::from_utf8(bytes.to_vec()).map_err(|_| {
+            VoxError::InvalidFile(format!("string at byte {} is not UTF-8", *cursor))
+        })?;
         *cursor = *cursor + 4 + size;
 
-        VoxString::new(size, string)
+        Ok(VoxString::new(size, string))
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_slice(buf_writer, &self.size.to_le_bytes());
-        write_slice(buf_writer, self.content.as_bytes());
+    pub fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
+        write_slice(buf_writer, &self.size.to_le_bytes())?;
+        write_slice(buf_writer, self.content.as_bytes())?;
+
+        Ok(())
     }
 
     pub fn new(size: i32, content: String) -> VoxString {
@@ -56,33 +73,31 @@ pub struct Dict {
 }
 
 impl Dict {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Dict {
+    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Result<Dict, VoxError> {
         let mut pairs = Vec::new();
 
-        let size = i32::from_le_bytes(
-            input[(*cursor as usize)..(4 + *cursor as usize)]
-                .try_into()
-                .expect("failed to read"),
-        );
+        let size = i32_from_vec(input, cursor)?;
         *cursor += 4;
         for _i in 0..size {
-            let key = VoxString::read(input, cursor);
-            let value = VoxString::read(input, cursor);
+            let key = VoxString::read(input, cursor)?;
+            let value = VoxString::read(input, cursor)?;
             pairs.push((key, value))
         }
 
-        Dict {
+        Ok(Dict {
             num_of_pairs: size,
             pairs,
-        }
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_slice(buf_writer, &self.num_of_pairs.to_le_bytes());
+    pub fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
+        write_slice(buf_writer, &self.num_of_pairs.to_le_bytes())?;
         for pair in self.pairs.iter() {
-            pair.0.write(buf_writer);
-            pair.1.write(buf_writer);
+            pair.0.write(buf_writer)?;
+            pair.1.write(buf_writer)?;
         }
+
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
@@ -105,35 +120,37 @@ pub struct nTRN {
     pub reserved_id: i32,
     //must be -1
     pub layer_id: i32,
-    //must be 1
     pub num_of_frames: i32,
     // for each frame
     // DICT	: frame attributes
     // (_r : int8) ROTATION, see (c)
     // (_t : int32x3) translation
+    // (_f : int32) frame index
     // }xN
-    pub frame_attributes: Dict,
+    pub frame_attributes: Vec<Dict>,
 }
 
 impl nTRN {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> nTRN {
+    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Result<nTRN, VoxError> {
         *cursor += 12;
         //need to make function for reading i32
-        let node_id = i32_from_vec(input, cursor);
+        let node_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let node_attributes = Dict::read(input, cursor);
-        let child_node_id = i32_from_vec(input, cursor);
+        let node_attributes = Dict::read(input, cursor)?;
+        let child_node_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let reserved_id = i32_from_vec(input, cursor);
+        let reserved_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let layer_id = i32_from_vec(input, cursor);
+        let layer_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let num_of_frames = i32_from_vec(input, cursor);
+        let num_of_frames = i32_from_vec(input, cursor)?;
         *cursor += 4;
 
-        let frame_attributes = Dict::read(input, cursor);
+        let frame_attributes = (0..num_of_frames)
+            .map(|_| Dict::read(input, cursor))
+            .collect::<Result<_, _>>()?;
 
-        nTRN {
+        Ok(nTRN {
             node_id,
             node_attributes,
             child_node_id,
@@ -141,62 +158,141 @@ impl nTRN {
             layer_id,
             num_of_frames,
             frame_attributes,
-        }
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
+    pub fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
         //change
-        write_chunk("nTRN", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.node_id.to_le_bytes());
-        self.node_attributes.write(buf_writer);
-        write_slice(buf_writer, &self.child_node_id.to_le_bytes());
-        write_slice(buf_writer, &self.reserved_id.to_le_bytes());
-        write_slice(buf_writer, &self.layer_id.to_le_bytes());
-        write_slice(buf_writer, &self.num_of_frames.to_le_bytes());
-        self.frame_attributes.write(buf_writer);
+        write_chunk("nTRN", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.node_id.to_le_bytes())?;
+        self.node_attributes.write(buf_writer)?;
+        write_slice(buf_writer, &self.child_node_id.to_le_bytes())?;
+        write_slice(buf_writer, &self.reserved_id.to_le_bytes())?;
+        write_slice(buf_writer, &self.layer_id.to_le_bytes())?;
+        write_slice(buf_writer, &self.num_of_frames.to_le_bytes())?;
+        for frame in self.frame_attributes.iter() {
+            frame.write(buf_writer)?;
+        }
+
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
-        20 + self.node_attributes.get_size() + self.frame_attributes.get_size()
+        let frames_size: i32 = self.frame_attributes.iter().map(Dict::get_size).sum();
+        20 + self.node_attributes.get_size() + frames_size
     }
 
-    pub fn to_node(&self) -> Node {
+    pub fn to_node(&self) -> Result<Node, VoxError> {
         let data = Transform {
             layer: self.layer_id,
-            rotation: self.has_rotation(),
-            translation: self.has_translation(),
+            frames: self
+                .frame_attributes
+                .iter()
+                .map(Frame::from_dict)
+                .collect::<Result<_, _>>()?,
         };
 
-        Node::new(
+        Ok(Node::new(
             NodeType::Transform(data),
-            node_attributes_from_dict(&self.node_attributes),
-        )
+            node_attributes_from_dict(&self.node_attributes)?,
+        ))
     }
+}
 
-    pub fn has_translation(&self) -> Option<(i32, i32, i32)> {
-        if !self.frame_attributes.pairs.is_empty() {
-            for attribute in self.frame_attributes.pairs.iter() {
-                if attribute.0.content == *"_t" {
-                    let parsed = parse_string(&attribute.1.content);
-                    return Some((parsed[0], parsed[1], parsed[2]));
-                }
+/// Rotation of a model stored as the single byte used by the `_r` attribute of a transform. The byte packs
+/// a 3x3 rotation matrix where every row has one entry that is 1 or -1:
+/// bits 0-1 are the column of the entry in the first row, bits 2-3 are the column of the entry in the
+/// second row, and bits 4, 5, and 6 are set when the entry in the first, second, or third row is -1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    byte: u8,
+}
+
+impl Rotation {
+    /// The rotation that does not change anything.
+    pub const IDENTITY: Rotation = Rotation { byte: 4 };
+
+    /// Packs a rotation matrix into a Rotation. Returns None if every row and column of the matrix does not
+    /// have exactly one entry that is 1 or -1 with the rest 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// let identity = Rotation::from_matrix([[1, 0, 0], [0, 1, 0], [0, 0, 1]]).unwrap();
+    /// assert_eq!(Rotation::IDENTITY, identity);
+    /// assert_eq!(4, identity.to_byte());
+    ///
+    /// //90 degrees around the z axis
+    /// let turned = Rotation::from_matrix([[0, -1, 0], [1, 0, 0], [0, 0, 1]]).unwrap();
+    /// assert_eq!(17, turned.to_byte());
+    ///
+    /// assert!(Rotation::from_matrix([[1, 1, 0], [0, 1, 0], [0, 0, 1]]).is_none());
+    /// assert!(Rotation::from_matrix([[1, 0, 0], [1, 0, 0], [0, 0, 1]]).is_none());
+    /// assert!(Rotation::from_matrix([[2, 0, 0], [0, 1, 0], [0, 0, 1]]).is_none());
+    /// ```
+    pub fn from_matrix(matrix: [[i8; 3]; 3]) -> Option<Rotation> {
+        let mut columns = [0; 3];
+        let mut byte = 0;
+        for (row, entries) in matrix.iter().enumerate() {
+            let mut non_zero = entries.iter().enumerate().filter(|(_, entry)| **entry != 0);
+            let (column, entry) = non_zero.next()?;
+            if non_zero.next().is_some() || entry.abs() != 1 {
+                return None;
+            }
+            columns[row] = column;
+            if *entry == -1 {
+                byte |= 1 << (4 + row);
             }
         }
+        if columns[0] == columns[1] || columns[0] == columns[2] || columns[1] == columns[2] {
+            return None;
+        }
 
-        None
+        byte |= columns[0] as u8 | (columns[1] as u8) << 2;
+        Some(Rotation { byte })
     }
 
-    pub fn has_rotation(&self) -> Option<i32> {
-        if !self.frame_attributes.pairs.is_empty() {
-            for attribute in self.frame_attributes.pairs.iter() {
-                if attribute.0.content == *"_r" {
-                    let parsed = parse_string(&attribute.1.content);
-                    return Some(parsed[0]);
-                }
-            }
+    /// Unpacks the rotation into a matrix.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::Rotation;
+    ///
+    /// let turned = Rotation::from_byte(17).unwrap();
+    /// assert_eq!([[0, -1, 0], [1, 0, 0], [0, 0, 1]], turned.to_matrix());
+    /// ```
+    pub fn to_matrix(&self) -> [[i8; 3]; 3] {
+        let first = (self.byte & 3) as usize;
+        let second = ((self.byte >> 2) & 3) as usize;
+        //the column that is not used by the first 2 rows
+        let third = 3 - first - second;
+
+        let mut matrix = [[0; 3]; 3];
+        for (row, column) in [first, second, third].iter().enumerate() {
+            matrix[row][*column] = if self.byte & (1 << (4 + row)) != 0 {
+                -1
+            } else {
+                1
+            };
         }
+        matrix
+    }
+
+    /// Makes a Rotation from the byte stored in a file, such as `Model::rotation`. Returns None if the byte
+    /// is not a valid rotation.
+    pub fn from_byte(byte: u8) -> Option<Rotation> {
+        let first = byte & 3;
+        let second = (byte >> 2) & 3;
+        if first > 2 || second > 2 || first == second || byte & 0x80 != 0 {
+            return None;
+        }
+        Some(Rotation { byte })
+    }
 
-        None
+    /// Gets the byte that is stored in a file.
+    pub fn to_byte(&self) -> u8 {
+        self.byte
     }
 }
 
@@ -215,46 +311,48 @@ pub struct nGRP {
 }
 
 impl nGRP {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> nGRP {
+    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Result<nGRP, VoxError> {
         *cursor += 12;
-        let node_id = i32_from_vec(input, cursor);
+        let node_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let node_attributes = Dict::read(input, cursor);
-        let num_of_children_nodes = i32_from_vec(input, cursor);
+        let node_attributes = Dict::read(input, cursor)?;
+        let num_of_children_nodes = i32_from_vec(input, cursor)?;
         *cursor += 4;
         let mut child_id = Vec::new();
         for _i in 0..num_of_children_nodes {
-            child_id.push(i32_from_vec(input, cursor));
+            child_id.push(i32_from_vec(input, cursor)?);
             *cursor += 4;
         }
 
-        nGRP {
+        Ok(nGRP {
             node_id,
             node_attributes,
             num_of_children_nodes,
             child_id,
-        }
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_chunk("nGRP", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.node_id.to_le_bytes());
-        self.node_attributes.write(buf_writer);
-        write_slice(buf_writer, &self.num_of_children_nodes.to_le_bytes());
+    pub fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
+        write_chunk("nGRP", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.node_id.to_le_bytes())?;
+        self.node_attributes.write(buf_writer)?;
+        write_slice(buf_writer, &self.num_of_children_nodes.to_le_bytes())?;
         for child_id in self.child_id.iter() {
-            write_slice(buf_writer, &child_id.to_le_bytes());
+            write_slice(buf_writer, &child_id.to_le_bytes())?;
         }
+
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
         8 + self.node_attributes.get_size() + self.child_id.len() as i32 * 4
     }
 
-    pub fn to_node(&self) -> Node {
-        Node::new(
+    pub fn to_node(&self) -> Result<Node, VoxError> {
+        Ok(Node::new(
             NodeType::Group,
-            node_attributes_from_dict(&self.node_attributes),
-        )
+            node_attributes_from_dict(&self.node_attributes)?,
+        ))
     }
 }
 
@@ -264,84 +362,117 @@ impl nGRP {
 pub struct nSHP {
     pub node_id: i32,
     pub node_attributes: Dict,
-    //must be 1
+    //more than 1 when the shape is animated
     pub num_of_models: i32,
     // for each model
     // {
     // int32	: model id
-    // DICT	: model attributes : reserved
+    // DICT	: model attributes
+    // (_f : int32) frame index
     // }xN
-    //only one model so only need one of each. may need to change if format changes
-    pub model_id: i32,
-    pub model_attributes: Dict,
+    //(model id, model attributes)
+    pub models: Vec<(i32, Dict)>,
 }
 
 impl nSHP {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> nSHP {
+    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Result<nSHP, VoxError> {
         *cursor += 12;
-        let node_id = i32_from_vec(input, cursor);
+        let node_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let node_attributes = Dict::read(input, cursor);
-        let num_of_models = i32_from_vec(input, cursor);
+        let node_attributes = Dict::read(input, cursor)?;
+        let num_of_models = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let model_id = i32_from_vec(input, cursor);
-        *cursor += 4;
-        let model_attributes = Dict::read(input, cursor);
+        let mut models = Vec::new();
+        for _i in 0..num_of_models {
+            let model_id = i32_from_vec(input, cursor)?;
+            *cursor += 4;
+            let model_attributes = Dict::read(input, cursor)?;
+            models.push((model_id, model_attributes));
+        }
 
-        nSHP {
+        Ok(nSHP {
             node_id,
             node_attributes,
             num_of_models,
-            model_id,
-            model_attributes,
-        }
+            models,
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_chunk("nSHP", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.node_id.to_le_bytes());
-        self.node_attributes.write(buf_writer);
-        write_slice(buf_writer, &self.num_of_models.to_le_bytes());
-        write_slice(buf_writer, &self.model_id.to_le_bytes());
-        self.model_attributes.write(buf_writer);
+    pub fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
+        write_chunk("nSHP", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.node_id.to_le_bytes())?;
+        self.node_attributes.write(buf_writer)?;
+        write_slice(buf_writer, &self.num_of_models.to_le_bytes())?;
+        for model in self.models.iter() {
+            write_slice(buf_writer, &model.0.to_le_bytes())?;
+            model.1.write(buf_writer)?;
+        }
+
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
-        12 + self.node_attributes.get_size() + self.model_attributes.get_size()
+        let mut size = 8 + self.node_attributes.get_size();
+        for model in self.models.iter() {
+            size += 4 + model.1.get_size();
+        }
+
+        size
     }
 
-    pub fn to_node(&self) -> Node {
-        Node::new(
-            NodeType::Shape(self.model_id),
-            node_attributes_from_dict(&self.node_attributes),
-        )
+    pub fn to_node(&self) -> Result<Node, VoxError> {
+        let mut models = Vec::new();
+        for model in self.models.iter() {
+            let mut frame = None;
+            for pair in model.1.pairs.iter() {
+                if pair.0.content == *"_f" {
+                    frame = Some(pair.1.content.parse::<i32>().map_err(|_| {
+                        VoxError::InvalidFile(format!(
+                            "model frame {:?} is not a number",
+                            pair.1.content
+                        ))
+                    })?);
+                }
+            }
+            models.push(ShapeModel {
+                model_id: model.0,
+                frame,
+            });
+        }
+
+        Ok(Node::new(
+            NodeType::Shape(models),
+            node_attributes_from_dict(&self.node_attributes)?,
+        ))
     }
 }
 
 #[allow(non_camel_case_types)]
 #[derive(Debug)]
 pub struct MATL {
-    material_id: i32,
-    properties: Dict,
+    pub material_id: i32,
+    pub properties: Dict,
 }
 
 impl MATL {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> MATL {
+    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Result<MATL, VoxError> {
         *cursor += 12;
-        let material_id = i32_from_vec(input, cursor);
+        let material_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let properties = Dict::read(input, cursor);
+        let properties = Dict::read(input, cursor)?;
 
-        MATL {
+        Ok(MATL {
             material_id,
             properties,
-        }
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_chunk("MATL", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.material_id.to_le_bytes());
-        self.properties.write(buf_writer);
+    pub fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
+        write_chunk("MATL", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.material_id.to_le_bytes())?;
+        self.properties.write(buf_writer)?;
+
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
@@ -359,26 +490,28 @@ pub struct LAYR {
 }
 
 impl LAYR {
-    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> LAYR {
+    pub fn read(input: &Vec<u8>, cursor: &mut i32) -> Result<LAYR, VoxError> {
         *cursor += 12;
-        let layer_id = i32_from_vec(input, cursor);
+        let layer_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
-        let layer_attributes = Dict::read(input, cursor);
-        let reserved_id = i32_from_vec(input, cursor);
+        let layer_attributes = Dict::read(input, cursor)?;
+        let reserved_id = i32_from_vec(input, cursor)?;
         *cursor += 4;
 
-        LAYR {
+        Ok(LAYR {
             layer_id,
             layer_attributes,
             reserved_id,
-        }
+        })
     }
 
-    pub fn write(&self, buf_writer: &mut BufWriter<File>) {
-        write_chunk("LAYR", self.get_size() as u32, 0, buf_writer);
-        write_slice(buf_writer, &self.layer_id.to_le_bytes());
-        self.layer_attributes.write(buf_writer);
-        write_slice(buf_writer, &self.reserved_id.to_le_bytes());
+    pub fn write(&self, buf_writer: &mut BufWriter<File>) -> io::Result<()> {
+        write_chunk("LAYR", self.get_size() as u32, 0, buf_writer)?;
+        write_slice(buf_writer, &self.layer_id.to_le_bytes())?;
+        self.layer_attributes.write(buf_writer)?;
+        write_slice(buf_writer, &self.reserved_id.to_le_bytes())?;
+
+        Ok(())
     }
 
     pub fn get_size(&self) -> i32 {
@@ -386,38 +519,32 @@ impl LAYR {
     }
 }
 
-//returns starting index. number 1 should return 1st chunk
-pub fn find_chunk(contents: &Vec<u8>, name: String, number: i32) -> Result<usize, ()> {
-    //currently breaks if can not find name
-    let mut chunk_name = String::new();
-    let mut chunk_size: u32;
-    let mut current_pos = 8;
-
-    let mut num_chunk = 1;
-
-    while chunk_name != name || num_chunk != (number + 1) {
-        //gets name of chunk
-        chunk_name = String::from_utf8(
-            contents[(current_pos as usize)..((current_pos + 4) as usize)].to_vec(),
-        )
-        .expect("failed to create string");
-
-        if chunk_name == name {
-            if num_chunk == number {
-                return Ok(current_pos as usize);
+//returns starting index of a chunk inside of MAIN. number 1 returns the 1st chunk with the name
+pub fn find_chunk(contents: &[u8], name: &str, number: i32) -> Result<usize, ()> {
+    let mut found = 0;
+    //skips the header and the MAIN chunk header
+    let mut current_pos: usize = 20;
+
+    //stops before a chunk header that is cut off at the end of the file
+    while current_pos + CHUNK_HEADER_SIZE as usize <= contents.len() {
+        if &contents[current_pos..current_pos + 4] == name.as_bytes() {
+            found += 1;
+            if found == number {
+                return Ok(current_pos);
             }
-            num_chunk += 1;
         }
-        current_pos += 4;
-        chunk_size = u32::from_le_bytes(
-            contents[(current_pos as usize)..((current_pos + 4) as usize)]
+
+        let content_size = u32::from_le_bytes(
+            contents[(current_pos + 4)..(current_pos + 8)]
                 .try_into()
                 .expect("failed to read"),
         );
-        current_pos += chunk_size + 8;
-        if current_pos >= contents.len() as u32 {
-            return Err(());
-        }
+        let children_size = u32::from_le_bytes(
+            contents[(current_pos + 8)..(current_pos + 12)]
+                .try_into()
+                .expect("failed to read"),
+        );
+        current_pos += CHUNK_HEADER_SIZE as usize + content_size as usize + children_size as usize;
     }
 
     Err(())
@@ -452,50 +579,89 @@ pub fn num_of_chunks(contents: &Vec<u8>, name: String) -> i32 {
     num_of_chunks
 }
 
+//names of the chunks inside of MAIN, in the order they are in the file
+pub fn chunk_names(contents: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    //skips the header and the MAIN chunk header
+    let mut current_pos: usize = 20;
+
+    while current_pos + CHUNK_HEADER_SIZE as usize <= contents.len() {
+        names.push(String::from_utf8_lossy(&contents[current_pos..current_pos + 4]).into_owned());
+        let content_size = u32::from_le_bytes(
+            contents[(current_pos + 4)..(current_pos + 8)]
+                .try_into()
+                .expect("failed to read"),
+        );
+        let children_size = u32::from_le_bytes(
+            contents[(current_pos + 8)..(current_pos + 12)]
+                .try_into()
+                .expect("failed to read"),
+        );
+        current_pos += CHUNK_HEADER_SIZE as usize + content_size as usize + children_size as usize;
+    }
+
+    names
+}
+
 //returns root node
-pub fn nodes_from_chunks(input: &Vec<u8>) -> Node {
+pub fn nodes_from_chunks(input: &Vec<u8>) -> Result<Node, VoxError> {
     //start of root node
-    let mut cursor = find_chunk(input, String::from("nTRN"), 1).unwrap() as i32;
-    let root_node_chunk = nTRN::read(input, &mut cursor);
-    let mut root_node = root_node_chunk.to_node();
+    let mut cursor = find_chunk(input, "nTRN", 1)
+        .map_err(|_| VoxError::InvalidFile(String::from("could not find nTRN chunk")))?
+        as i32;
+    let root_node_chunk = nTRN::read(input, &mut cursor)?;
+    let mut root_node = root_node_chunk.to_node()?;
 
-    add_node_children(&mut root_node, 1, &mut cursor, input);
+    add_node_children(&mut root_node, 1, &mut cursor, input)?;
 
-    root_node
+    Ok(root_node)
 }
 
-pub fn chunk_name(vec: &Vec<u8>, pos: &mut i32) -> String {
-    String::from_utf8(vec[(*pos as usize)..((*pos + 4) as usize)].to_vec())
-        .expect("failed to create string")
+pub fn chunk_name(vec: &Vec<u8>, pos: &mut i32) -> Result<String, VoxError> {
+    String::from_utf8(read_bytes(vec, *pos, 4)?.to_vec())
+        .map_err(|_| VoxError::InvalidFile(format!("chunk name at byte {} is not UTF-8", *pos)))
 }
 
-pub fn i32_from_vec(vec: &Vec<u8>, pos: &mut i32) -> i32 {
-    i32::from_le_bytes(
-        vec[(*pos as usize)..(4 + *pos as usize)]
-            .try_into()
-            .expect("failed to create i32"),
-    )
+pub fn i32_from_vec(vec: &Vec<u8>, pos: &mut i32) -> Result<i32, VoxError> {
+    let bytes = read_bytes(vec, *pos, 4)?;
+    Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
 }
 
-pub fn parse_string(string: &String) -> Vec<i32> {
-    let a: Vec<&str> = string.split(' ').collect();
-    let mut num: Vec<i32> = Vec::new();
-    a.iter()
-        .for_each(|string| num.push(string.parse().unwrap()));
+//the bytes from start to start + length, or an error if they are not all inside of the contents
+fn read_bytes(contents: &[u8], start: i32, length: i32) -> Result<&[u8], VoxError> {
+    if start >= 0 && length >= 0 {
+        let start = start as usize;
+        if let Some(bytes) = contents.get(start..start + length as usize) {
+            return Ok(bytes);
+        }
+    }
+    Err(VoxError::InvalidFile(format!(
+        "{} bytes at byte {} go past the end of the file",
+        length, start
+    )))
+}
 
-    num
+//numbers separated by spaces, like the "_t" translation of a frame
+pub fn parse_string(string: &String) -> Result<Vec<i32>, VoxError> {
+    string
+        .split(' ')
+        .map(|number| {
+            number
+                .parse()
+                .map_err(|_| VoxError::InvalidFile(format!("{:?} is not a number", number)))
+        })
+        .collect()
 }
 
-pub fn node_attributes_from_dict(dict: &Dict) -> NodeAttributes {
+pub fn node_attributes_from_dict(dict: &Dict) -> Result<NodeAttributes, VoxError> {
     let mut name = None;
     let mut hidden = None;
     for pair in dict.pairs.iter() {
         if pair.0.content == *"_hidden" {
-            hidden = if (pair.1.content.parse::<i32>().unwrap()) == 1 {
-                Some(true)
-            } else {
-                Some(false)
-            }
+            let value = pair.1.content.parse::<i32>().map_err(|_| {
+                VoxError::InvalidFile(format!("hidden value {:?} is not a number", pair.1.content))
+            })?;
+            hidden = Some(value == 1);
         }
 
         if pair.0.content == *"_name" {
@@ -503,7 +669,7 @@ pub fn node_attributes_from_dict(dict: &Dict) -> NodeAttributes {
         }
     }
 
-    NodeAttributes { name, hidden }
+    Ok(NodeAttributes { name, hidden })
 }
 
 pub fn add_node_children(
@@ -511,24 +677,126 @@ pub fn add_node_children(
     num_of_children: i32,
     cursor: &mut i32,
     contents: &Vec<u8>,
-) {
+) -> Result<(), VoxError> {
     for _i in 0..num_of_children {
-        let name = chunk_name(contents, cursor);
+        //a file that ends before all of the children are read has no more nodes
+        if *cursor as usize + CHUNK_HEADER_SIZE as usize > contents.len() {
+            return Ok(());
+        }
+        let name = chunk_name(contents, cursor)?;
         if name == *"nTRN" {
-            let chunk = nTRN::read(contents, cursor);
-            let mut new_node = chunk.to_node();
-            add_node_children(&mut new_node, 1, cursor, contents);
+            let chunk = nTRN::read(contents, cursor)?;
+            let mut new_node = chunk.to_node()?;
+            add_node_children(&mut new_node, 1, cursor, contents)?;
             node.add_child(new_node);
         } else if name == *"nSHP" {
-            let chunk = nSHP::read(contents, cursor);
-            let new_node = chunk.to_node();
+            let chunk = nSHP::read(contents, cursor)?;
+            let new_node = chunk.to_node()?;
             node.add_child(new_node);
         } else if name == *"nGRP" {
-            let chunk = nGRP::read(contents, cursor);
+            let chunk = nGRP::read(contents, cursor)?;
             let num_children = chunk.num_of_children_nodes;
-            let mut new_node = chunk.to_node();
-            add_node_children(&mut new_node, num_children, cursor, contents);
+            let mut new_node = chunk.to_node()?;
+            add_node_children(&mut new_node, num_children, cursor, contents)?;
             node.add_child(new_node);
         }
     }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_size_too_large() {
+        assert_eq!(u32::MAX, chunk_size(u32::MAX as u64).unwrap());
+        assert!(matches!(
+            chunk_size(u32::MAX as u64 + 1),
+            Err(VoxError::TooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn multi_frame_transform_round_trips() {
+        let frames = vec![
+            Frame {
+                rotation: Some(17),
+                translation: Some((1, -2, 3)),
+                index: Some(0),
+            },
+            Frame {
+                rotation: None,
+                translation: Some((10, 20, -30)),
+                index: Some(12),
+            },
+        ];
+        let chunk = nTRN {
+            node_id: 3,
+            node_attributes: NodeAttributes::new().to_dict(),
+            child_node_id: 4,
+            reserved_id: -1,
+            layer_id: 0,
+            num_of_frames: 2,
+            frame_attributes: frames.iter().map(Frame::to_dict).collect(),
+        };
+
+        let path = std::env::temp_dir().join("create_vox_two_frames.chunk");
+        {
+            let mut writer = BufWriter::new(File::create(&path).unwrap());
+            chunk.write(&mut writer).unwrap();
+        }
+        let contents = std::fs::read(&path).unwrap();
+        assert_eq!(
+            CHUNK_HEADER_SIZE as usize + chunk.get_size() as usize,
+            contents.len()
+        );
+
+        let mut cursor = 0;
+        let read = nTRN::read(&contents, &mut cursor).unwrap();
+        assert_eq!(contents.len(), cursor as usize);
+        assert_eq!(4, read.child_node_id);
+        match read.to_node().unwrap().node_type {
+            NodeType::Transform(transform) => assert_eq!(frames, transform.frames),
+            _ => panic!("expected a transform node"),
+        }
+    }
+
+    #[test]
+    fn find_chunk_returns_each_occurrence() {
+        let mut vox = crate::VoxFile::new(5, 5, 5);
+        vox.add_model(crate::Model::new(5, 5, 5));
+        let path = std::env::temp_dir().join("create_vox_find_chunk.vox");
+        vox.save(&path).unwrap();
+        let contents = std::fs::read(&path).unwrap();
+
+        //the root transform and one transform for each model
+        assert_eq!(3, num_of_chunks(&contents, String::from("nTRN")));
+        let first = find_chunk(&contents, "nTRN", 1).unwrap();
+        let second = find_chunk(&contents, "nTRN", 2).unwrap();
+        let third = find_chunk(&contents, "nTRN", 3).unwrap();
+        assert!(first < second && second < third);
+        for position in [first, second, third].iter() {
+            assert_eq!(b"nTRN", &contents[*position..*position + 4]);
+        }
+        assert_eq!(Err(()), find_chunk(&contents, "nTRN", 4));
+        assert_eq!(Err(()), find_chunk(&contents, "nTRN", 0));
+
+        //a chunk header cut off at the end of the file is not read
+        let truncated = &contents[..contents.len() - 1030];
+        assert_eq!(Ok(first), find_chunk(truncated, "nTRN", 1));
+        assert_eq!(Err(()), find_chunk(truncated, "RGBA", 1));
+        assert_eq!(Err(()), find_chunk(&contents[..first + 6], "nTRN", 2));
+    }
+
+    #[test]
+    fn every_rotation_byte_round_trips() {
+        let valid: Vec<Rotation> = (0..=255).filter_map(Rotation::from_byte).collect();
+        //6 orders of the columns and 8 combinations of signs
+        assert_eq!(48, valid.len());
+        for rotation in valid {
+            assert_eq!(Some(rotation), Rotation::from_matrix(rotation.to_matrix()));
+        }
+    }
 }
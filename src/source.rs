@@ -0,0 +1,64 @@
+use crate::{Model, Voxel};
+
+/// Something that can give the color of a voxel at any position, so models can be made from functions
+/// without making models in between. Sources can be put together by making a source that uses others.
+///
+/// # Example
+/// ```
+/// use create_vox::{Model, VoxelSource};
+///
+/// struct Checkerboard;
+///
+/// impl VoxelSource for Checkerboard {
+///     fn color_at(&self, x: u8, y: u8, z: u8) -> Option<u8> {
+///         if (x + y + z) % 2 == 0 {
+///             Some(1)
+///         } else {
+///             None
+///         }
+///     }
+///
+///     fn size(&self) -> (u16, u16, u16) {
+///         (4, 4, 4)
+///     }
+/// }
+///
+/// let model = Model::from_source(&Checkerboard);
+/// assert_eq!((4, 4, 4), model.size);
+/// assert_eq!(32, model.num_of_voxels());
+/// assert!(model.is_voxel_at_pos(1, 0, 1));
+/// assert!(!model.is_voxel_at_pos(1, 0, 0));
+/// ```
+pub trait VoxelSource {
+    /// Returns the color index of the voxel at the position, or None if there is no voxel.
+    fn color_at(&self, x: u8, y: u8, z: u8) -> Option<u8>;
+
+    /// Returns the size of the model the source fills.
+    fn size(&self) -> (u16, u16, u16);
+}
+
+impl Model {
+    /// Creates a new model with the size of the source, with a voxel at every position the source has a
+    /// color for. Positions with color index 0 are left empty.
+    pub fn from_source(source: &impl VoxelSource) -> Model {
+        let size = source.size();
+        let mut model = Model::new(size.0, size.1, size.2);
+
+        //positions can only go up to 255
+        for x in 0..size.0.min(256) {
+            for y in 0..size.1.min(256) {
+                for z in 0..size.2.min(256) {
+                    if let Some(color) = source.color_at(x as u8, y as u8, z as u8) {
+                        if color != 0 {
+                            model
+                                .voxels
+                                .push(Voxel::new(x as u8, y as u8, z as u8, color));
+                        }
+                    }
+                }
+            }
+        }
+
+        model
+    }
+}
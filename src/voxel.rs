@@ -1,3 +1,4 @@
+use std::fmt;
 use std::ops::Add;
 
 /// A single voxel.
@@ -45,6 +46,17 @@ impl PartialEq for Voxel {
     }
 }
 
+//prints as (x,y,z):color
+impl fmt::Debug for Voxel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({},{},{}):{}",
+            self.position.0, self.position.1, self.position.2, self.color_index
+        )
+    }
+}
+
 impl Add for Voxel {
     type Output = Vec<Voxel>;
 
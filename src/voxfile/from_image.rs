@@ -0,0 +1,112 @@
+use crate::{Color, VoxError, VoxFile};
+use std::collections::HashMap;
+use std::path::Path;
+
+//index 0 is empty so only 255 colors can be used
+const MAX_COLORS: usize = 255;
+
+impl VoxFile {
+    /// Sets the palette to the colors in an image. Each different color in the image is put on the palette
+    /// once in the order they are first found, starting at index 1. If the image has more than 255 colors
+    /// they are reduced to 255 colors that are close to them. Indexes that are not needed keep their color.
+    ///
+    /// Only available with the `image` feature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.palette_from_image("colors.png").unwrap();
+    /// ```
+    pub fn palette_from_image<P: AsRef<Path>>(&mut self, path: P) -> Result<(), VoxError> {
+        let image = image::open(path)
+            .map_err(|error| match error {
+                image::ImageError::IoError(error) => VoxError::Io(error),
+                error => VoxError::InvalidFile(error.to_string()),
+            })?
+            .to_rgba8();
+
+        //counts are kept so that colors used more have more effect when reducing
+        let mut colors: Vec<(Color, u32)> = Vec::new();
+        let mut positions = HashMap::new();
+        for pixel in image.pixels() {
+            let color = Color::new(pixel[0], pixel[1], pixel[2], pixel[3]);
+            let position = *positions.entry(channels(&color)).or_insert_with(|| {
+                colors.push((color, 0));
+                colors.len() - 1
+            });
+            colors[position].1 += 1;
+        }
+
+        let colors = if colors.len() > MAX_COLORS {
+            median_cut(colors, MAX_COLORS)
+        } else {
+            colors.into_iter().map(|(color, _)| color).collect()
+        };
+        for (slot, color) in colors.into_iter().enumerate() {
+            self.palette[slot] = color;
+        }
+
+        Ok(())
+    }
+}
+
+//splits the colors into groups until there are max_colors groups, then uses the average of each group
+fn median_cut(colors: Vec<(Color, u32)>, max_colors: usize) -> Vec<Color> {
+    let mut groups = vec![colors];
+    while groups.len() < max_colors {
+        let widest = groups
+            .iter()
+            .enumerate()
+            .filter(|(_, group)| group.len() > 1)
+            .map(|(index, group)| {
+                let (channel, range) = widest_channel(group);
+                (index, channel, range)
+            })
+            .max_by_key(|&(_, _, range)| range);
+        let (index, channel, _) = match widest {
+            Some(widest) => widest,
+            None => break,
+        };
+
+        let mut group = groups.swap_remove(index);
+        group.sort_by_key(|(color, _)| channels(color)[channel]);
+        let other = group.split_off(group.len() / 2);
+        groups.push(group);
+        groups.push(other);
+    }
+
+    groups.iter().map(|group| average(group)).collect()
+}
+
+//the channel with the largest difference between colors and how large it is
+fn widest_channel(group: &[(Color, u32)]) -> (usize, u8) {
+    (0..4)
+        .map(|channel| {
+            let values = group.iter().map(|(color, _)| channels(color)[channel]);
+            let min = values.clone().min().unwrap_or(0);
+            let max = values.max().unwrap_or(0);
+            (channel, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap()
+}
+
+fn average(group: &[(Color, u32)]) -> Color {
+    let mut total = [0u64; 4];
+    let mut count = 0u64;
+    for (color, uses) in group.iter() {
+        for (channel, value) in channels(color).iter().enumerate() {
+            total[channel] += *value as u64 * *uses as u64;
+        }
+        count += *uses as u64;
+    }
+
+    let channel = |index: usize| (total[index] / count.max(1)) as u8;
+    Color::new(channel(0), channel(1), channel(2), channel(3))
+}
+
+fn channels(color: &Color) -> [u8; 4] {
+    [color.r, color.g, color.b, color.a]
+}
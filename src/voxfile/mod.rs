@@ -1,6 +1,12 @@
+pub use palette::PalettePolicy;
 pub use voxfile::VoxFile;
 
+#[cfg(feature = "image")]
+mod from_image;
 mod palette;
 mod read;
 mod voxfile;
 mod write;
+
+//version written in the file header
+pub(crate) const VERSION: u32 = 150;
@@ -1,4 +1,5 @@
-use crate::{Color, VoxFile};
+use crate::color::{from_hsv, to_hsv};
+use crate::{Color, ColorMetric, VoxError, VoxFile, Voxel};
 
 impl VoxFile {
     /// sets the color of a index on the palette. The index used can not be 0.
@@ -38,7 +39,8 @@ impl VoxFile {
     /// vox.add_gradient(1, 50, red, blue);
     /// ```
     pub fn add_gradient(&mut self, index1: u8, index2: u8, color1: Color, color2: Color) {
-        for i in index1..index2 {
+        //index 0 is empty so it does not have a color
+        for i in index1.max(1)..index2 {
             let fraction_between = ((i - index1) as f32) / ((index2 - index1) as f32);
             self.set_palette_color(
                 i,
@@ -50,17 +52,97 @@ impl VoxFile {
         }
     }
 
-    /// resets all colors in palette to grey
+    /// Makes a gradient from start_color at index start to end_color at index end, with both indexes
+    /// included. Index 0 is empty and does not have a color, so a start of 0 is changed to 1. Nothing is
+    /// changed if start is after end.
     ///
     /// # Example
     /// ```
     /// use create_vox::{VoxFile, Color};
     ///
     /// let mut vox = VoxFile::new(10, 10, 10);
-    /// let red = Color::new(255, 0, 0 , 255);
-    /// let blue = Color::new(0, 0, 255, 255);
+    /// let black = Color::new(0, 0, 0, 255);
+    /// let white = Color::new(255, 255, 255, 255);
     ///
-    /// vox.add_gradient(1, 50, red, blue);
+    /// vox.set_gradient(1, 5, black, white);
+    /// assert_eq!(black, vox.get_palette_color(1));
+    /// assert_eq!(white, vox.get_palette_color(5));
+    /// ```
+    pub fn set_gradient(&mut self, start: u8, end: u8, start_color: Color, end_color: Color) {
+        let start = start.max(1);
+        if start > end {
+            return;
+        }
+        self.fill_gradient(start, end, start_color, end_color);
+    }
+
+    /// Makes a gradient from the start color at start_index to the end color at end_index, with both
+    /// indexes included. It will return an error if start_index is 0, since index 0 is empty and does not
+    /// have a color, or if start_index is after end_index. When both indexes are the same only that index
+    /// is set, to the start color.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, VoxError, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let black = Color::new(0, 0, 0, 255);
+    /// let white = Color::new(255, 255, 255, 255);
+    ///
+    /// vox.set_palette_gradient(1, 3, black, white).unwrap();
+    /// assert_eq!(Color::new(127, 127, 127, 255), vox.get_palette_color(2));
+    ///
+    /// let error = vox.set_palette_gradient(5, 4, black, white).unwrap_err();
+    /// assert!(matches!(error, VoxError::InvalidRange { start: 5, end: 4 }));
+    /// ```
+    pub fn set_palette_gradient(
+        &mut self,
+        start_index: u8,
+        end_index: u8,
+        start: Color,
+        end: Color,
+    ) -> Result<(), VoxError> {
+        if start_index == 0 {
+            return Err(VoxError::InvalidColorIndex(0));
+        }
+        if start_index > end_index {
+            return Err(VoxError::InvalidRange {
+                start: start_index,
+                end: end_index,
+            });
+        }
+
+        self.fill_gradient(start_index, end_index, start, end);
+        Ok(())
+    }
+
+    //start needs to be between 1 and end
+    fn fill_gradient(&mut self, start: u8, end: u8, start_color: Color, end_color: Color) {
+        for i in start..=end {
+            let fraction_between = if start == end {
+                0.0
+            } else {
+                ((i - start) as f32) / ((end - start) as f32)
+            };
+            self.set_palette_color(
+                i,
+                get_middle(start_color.r, end_color.r, fraction_between),
+                get_middle(start_color.g, end_color.g, fraction_between),
+                get_middle(start_color.b, end_color.b, fraction_between),
+                get_middle(start_color.a, end_color.a, fraction_between),
+            )
+        }
+    }
+
+    /// resets all colors in palette to grey
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{VoxFile, Color};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.reset_palette();
+    /// assert_eq!(Color::new(75, 75, 75, 255), vox.get_palette_color(1));
     /// ```
     pub fn reset_palette(&mut self) {
         self.palette = [Color {
@@ -90,6 +172,23 @@ impl VoxFile {
         self.palette[new_index as usize]
     }
 
+    /// Gets the index on the palette (1 to 255) with the color closest to the color given, using the
+    /// straight line distance between the red, green, and blue values. Alpha is not used, and the lowest
+    /// index is used if there is a tie.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.set_palette_color(5, 255, 0, 0, 255);
+    /// assert_eq!(5, vox.nearest_color_index(Color::new(250, 5, 5, 255)));
+    /// ```
+    pub fn nearest_color_index(&self, color: Color) -> u8 {
+        //the last color on the palette is for index 0, which can not be used
+        color.nearest_in(&self.palette[..255], ColorMetric::Euclidean) as u8 + 1
+    }
+
     /// Like set_palette_color() but sets the color of all indexes on palette
     ///
     /// # Example
@@ -108,6 +207,139 @@ impl VoxFile {
             self.palette[i as usize].a = a;
         }
     }
+
+    /// Turns the hue of every color on the palette by the degrees given, keeping the saturation,
+    /// brightness, and alpha. The voxels are not changed, so they keep their indexes but get the new
+    /// colors. Shifting by 180 degrees gives the opposite colors, such as cyan for red.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.set_palette_color(1, 255, 0, 0, 255);
+    /// vox.shift_hue(120.0);
+    /// assert_eq!(Color::new(0, 255, 0, 255), vox.get_palette_color(1));
+    /// ```
+    pub fn shift_hue(&mut self, degrees: f32) {
+        for color in self.palette.iter_mut() {
+            let (hue, saturation, value) = to_hsv(color);
+            *color = from_hsv(hue + degrees, saturation, value, color.a);
+        }
+    }
+
+    /// Counts how many voxels use each color index across all of the models. The count for a color index
+    /// is at that index of the array, so index 0 is always 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{VoxFile, Model};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    /// vox.models[0].add_voxel_at_pos(2, 1, 1, 4).unwrap();
+    ///
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_voxel_at_pos(1, 1, 1, 4).unwrap();
+    /// vox.add_model(model);
+    ///
+    /// let usage = vox.palette_usage();
+    /// assert_eq!(1, usage[3]);
+    /// assert_eq!(2, usage[4]);
+    /// assert_eq!(0, usage[5]);
+    /// ```
+    pub fn palette_usage(&self) -> [u32; 256] {
+        let mut usage = [0; 256];
+        for model in self.models.iter() {
+            for voxel in model.voxels.iter() {
+                usage[voxel.color_index as usize] += 1;
+            }
+        }
+
+        usage
+    }
+
+    /// Adds a voxel with the color given to a model, so colors do not need to be put on the palette first.
+    /// An index that already has the color is used, otherwise the color replaces the color on the highest
+    /// index that no voxels use. Indexes are taken from 255 down, so colors set from index 1 up with
+    /// set_palette() are kept as long as there are free indexes above them. It will return an error if
+    /// there is no model at model_index, if a voxel could not be added at the position, or if every index
+    /// is used by a different color. The palette is not changed when there is an error.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let orange = Color::new(255, 120, 0, 255);
+    /// vox.add_voxel_rgba(0, 1, 1, 1, orange).unwrap();
+    /// vox.add_voxel_rgba(0, 2, 1, 1, orange).unwrap();
+    ///
+    /// let index = vox.models[0].voxels[0].color_index;
+    /// assert_eq!(index, vox.models[0].voxels[1].color_index);
+    /// assert_eq!(orange, vox.get_palette_color(index));
+    /// ```
+    pub fn add_voxel_rgba(
+        &mut self,
+        model_index: usize,
+        x: u8,
+        y: u8,
+        z: u8,
+        color: Color,
+    ) -> Result<(), VoxError> {
+        self.models
+            .get(model_index)
+            .ok_or(VoxError::InvalidModelIndex(model_index))?
+            .check_can_add(x, y, z)?;
+
+        let index = match (1..=255).find(|index| self.get_palette_color(*index) == color) {
+            Some(index) => index,
+            None => {
+                let usage = self.palette_usage();
+                let index = (1..=255u8)
+                    .rev()
+                    .find(|index| usage[*index as usize] == 0)
+                    .ok_or(VoxError::PaletteFull)?;
+                self.set_palette_color(index, color.r, color.g, color.b, color.a);
+                index
+            }
+        };
+
+        self.models[model_index].add_voxel(Voxel::new(x, y, z, index))
+    }
+
+    /// Puts the colors on the palette starting at index 1. Colors after the 255th are not used. The policy
+    /// decides what happens to the indexes after the last color.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Color, PalettePolicy, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let colors = [Color::new(255, 0, 0, 255), Color::new(0, 255, 0, 255)];
+    /// vox.set_palette(&colors, PalettePolicy::ClearRest);
+    ///
+    /// assert_eq!(colors[1], vox.get_palette_color(2));
+    /// assert_eq!(Color::new(0, 0, 0, 0), vox.get_palette_color(3));
+    /// ```
+    pub fn set_palette(&mut self, colors: &[Color], policy: PalettePolicy) {
+        let count = colors.len().min(255);
+        self.palette[..count].copy_from_slice(&colors[..count]);
+        if policy == PalettePolicy::ClearRest {
+            for color in self.palette[count..].iter_mut() {
+                *color = Color::new(0, 0, 0, 0);
+            }
+        }
+    }
+}
+
+/// What happens to the indexes on the palette that are not given a color by set_palette().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PalettePolicy {
+    /// The indexes keep the color they had before.
+    LeaveRest,
+    /// The indexes are set to a transparent black.
+    ClearRest,
 }
 
 fn get_middle(a: u8, b: u8, point_between: f32) -> u8 {
@@ -1,59 +1,248 @@
 use crate::layer::Layer;
+use crate::material::Material;
 use crate::model::Model;
+use crate::node::{Node, NodeAttributes, NodeType};
 use crate::riff;
-use crate::riff::{num_of_chunks, LAYR};
+use crate::riff::{CHUNK_HEADER_SIZE, LAYR, MATL};
 use crate::voxfile::VoxFile;
-use crate::Color;
-use std::fs::File;
-use std::io::Read;
+use crate::{Color, VoxError, Warning};
+use std::path::Path;
+
+//versions written by MagicaVoxel
+const SUPPORTED_VERSIONS: [u32; 2] = [150, 200];
+
+//chunks written by MagicaVoxel, not all of them are used
+const KNOWN_CHUNKS: [&str; 15] = [
+    "PACK", "SIZE", "XYZI", "RGBA", "nTRN", "nGRP", "nSHP", "MATL", "MATT", "LAYR", "rOBJ", "rCAM",
+    "NOTE", "IMAP", "META",
+];
 
 impl VoxFile {
-    pub fn load(path: &str) -> VoxFile {
-        //read file
-        let mut file = File::open(path).expect("failed to open file");
-        let mut contents = Vec::new();
-        file.read_to_end(&mut contents)
-            .expect("failed to read contents");
+    pub fn load(path: impl AsRef<Path>) -> VoxFile {
+        VoxFile::open(path).expect("failed to load file")
+    }
+
+    /// Opens a .vox file. It will return an error if the file can not be read, VoxError::NotAVoxFile if
+    /// it does not start with the VOX header, or VoxError::InvalidFile if the chunks in it are broken, such
+    /// as a chunk that goes past the end of the file. The path can be anything that can be used as a Path, such as a &str, String, or PathBuf.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    /// use std::path::PathBuf;
+    ///
+    /// let path: PathBuf = std::env::temp_dir().join("open_example.vox");
+    /// let mut vox = VoxFile::new(5, 5, 5);
+    /// vox.models[0].add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    /// vox.save(&path).unwrap();
+    ///
+    /// let from_path_buf = VoxFile::open(&path).unwrap();
+    /// let from_str = VoxFile::open(path.to_str().unwrap()).unwrap();
+    /// assert!(from_path_buf.models[0].is_voxel_at_pos(1, 2, 3));
+    /// assert!(from_str.models[0].is_voxel_at_pos(1, 2, 3));
+    /// ```
+    pub fn open(path: impl AsRef<Path>) -> Result<VoxFile, VoxError> {
+        VoxFile::from_contents(read_contents(path)?)
+    }
+
+    /// Reads a .vox file that is already in memory. It returns the same errors as open() other than
+    /// VoxError::Io.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{VoxError, VoxFile};
+    ///
+    /// let path = std::env::temp_dir().join("from_bytes_example.vox");
+    /// let mut vox = VoxFile::new(5, 5, 5);
+    /// vox.models[0].add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    /// vox.save(&path).unwrap();
+    ///
+    /// let bytes = std::fs::read(&path).unwrap();
+    /// assert!(VoxFile::from_bytes(&bytes).unwrap().models[0].is_voxel_at_pos(1, 2, 3));
+    /// assert!(matches!(VoxFile::from_bytes(b"hello"), Err(VoxError::NotAVoxFile)));
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<VoxFile, VoxError> {
+        check_header(bytes)?;
+        VoxFile::from_contents(bytes.to_vec())
+    }
+
+    /// Loads a file like load(), but also returns problems with the file that did not stop it from loading,
+    /// such as chunks that are not known or a version that has not been tested.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let path = std::env::temp_dir().join("open_with_warnings_example.vox");
+    /// let mut vox = VoxFile::new(5, 5, 5);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    /// vox.save(&path).unwrap();
+    ///
+    /// let (loaded, warnings) = VoxFile::open_with_warnings(&path).unwrap();
+    /// assert!(warnings.is_empty());
+    /// assert_eq!(1, loaded.models[0].num_of_voxels());
+    /// ```
+    pub fn open_with_warnings(path: impl AsRef<Path>) -> Result<(VoxFile, Vec<Warning>), VoxError> {
+        let contents = read_contents(path)?;
+
+        let mut warnings = Vec::new();
+        let version = u32::from_le_bytes([contents[4], contents[5], contents[6], contents[7]]);
+        if !SUPPORTED_VERSIONS.contains(&version) {
+            warnings.push(Warning::UnexpectedVersion(version));
+        }
+        for name in riff::chunk_names(&contents) {
+            if !KNOWN_CHUNKS.contains(&name.as_str()) {
+                warnings.push(Warning::UnknownChunk(name));
+            }
+        }
+
+        Ok((VoxFile::from_contents(contents)?, warnings))
+    }
+
+    fn from_contents(contents: Vec<u8>) -> Result<VoxFile, VoxError> {
+        //after this every chunk, model, and palette is known to be inside of the contents
+        check_chunks(&contents)?;
+        let names = riff::chunk_names(&contents);
+        let count = |name: &str| names.iter().filter(|chunk| *chunk == name).count() as i32;
+        let find = |name: &str, number: i32| {
+            riff::find_chunk(&contents, name, number)
+                .map_err(|_| VoxError::InvalidFile(format!("could not find {} chunk", name)))
+        };
 
         //gets models
-        let num_of_models = riff::num_of_chunks(&contents, String::from("SIZE"));
         let mut models = Vec::new();
-
-        for i in 1..(num_of_models + 1) {
-            let mut chunk_pos = riff::find_chunk(&contents, String::from("SIZE"), i)
-                .expect("could not find SIZE chunk") as i32;
-            models.push(Model::read(&contents, &mut chunk_pos, i - 1));
+        for i in 1..(count("SIZE") + 1) {
+            let mut chunk_pos = find("SIZE", i)? as i32;
+            models.push(Model::read(&contents, &mut chunk_pos, i - 1)?);
         }
 
-        //palette
-        let mut palette: [Color; 256] = [Color::new(0, 0, 0, 0); 256];
-        let current_pos = riff::find_chunk(&contents, String::from("RGBA"), 1).unwrap() + 12;
-        for i in 0..256 {
-            //gets the color data
-            let r: u8 = contents[(current_pos + (i * 4)) as usize];
-            let g: u8 = contents[(current_pos + (i * 4) + 1) as usize];
-            let b: u8 = contents[(current_pos + (i * 4) + 2) as usize];
-            let a: u8 = contents[(current_pos + (i * 4) + 3) as usize];
-            palette[i as usize] = Color::new(r, g, b, a);
+        //palette, files without one use the default palette
+        let mut palette = Color::default_palette();
+        if let Ok(chunk_pos) = riff::find_chunk(&contents, "RGBA", 1) {
+            let current_pos = chunk_pos + CHUNK_HEADER_SIZE as usize;
+            for (i, color) in palette.iter_mut().enumerate() {
+                //gets the color data
+                let bytes = &contents[current_pos + i * 4..current_pos + i * 4 + 4];
+                *color = Color::new(bytes[0], bytes[1], bytes[2], bytes[3]);
+            }
         }
 
         let mut layers = Vec::new();
-        for i in 1..(num_of_chunks(&contents, String::from("LAYR")) + 1) {
-            let mut chunk_pos = riff::find_chunk(&contents, String::from("LAYR"), i)
-                .expect("could not find SIZE chunk") as i32;
-            layers.push(Layer::from_chunk(LAYR::read(&contents, &mut chunk_pos)));
+        for i in 1..(count("LAYR") + 1) {
+            let mut chunk_pos = find("LAYR", i)? as i32;
+            layers.push(Layer::from_chunk(LAYR::read(&contents, &mut chunk_pos)?)?);
+        }
+
+        let mut materials = Vec::new();
+        for i in 1..(count("MATL") + 1) {
+            let mut chunk_pos = find("MATL", i)? as i32;
+            materials.push(Material::from_chunk(MATL::read(&contents, &mut chunk_pos)?));
         }
 
+        //older files do not have nodes
+        let root_node = if count("nTRN") > 0 {
+            riff::nodes_from_chunks(&contents)?
+        } else {
+            Node::new(NodeType::Group, NodeAttributes::new())
+        };
+
         let mut voxfile = VoxFile {
             models,
             palette,
-            root_node: riff::nodes_from_chunks(&contents),
+            root_node,
             layers,
             copies: vec![],
+            materials,
         };
 
         voxfile.get_node_data();
 
-        voxfile
+        Ok(voxfile)
+    }
+}
+
+//reads the whole file and checks that it is a .vox file
+fn read_contents(path: impl AsRef<Path>) -> Result<Vec<u8>, VoxError> {
+    let contents = std::fs::read(path)?;
+    check_header(&contents)?;
+    Ok(contents)
+}
+
+//the header is the "VOX " magic and the version
+fn check_header(contents: &[u8]) -> Result<(), VoxError> {
+    if contents.len() < 8 || &contents[0..4] != b"VOX " {
+        return Err(VoxError::NotAVoxFile);
+    }
+    Ok(())
+}
+
+//goes through the chunks inside of MAIN and checks that each one is inside of the contents, that each SIZE
+//chunk is followed by an XYZI chunk that has all of its voxels, and that the palette has all 256 colors
+fn check_chunks(contents: &[u8]) -> Result<(), VoxError> {
+    let header_size = CHUNK_HEADER_SIZE as usize;
+    let read_u32 = |at: usize| {
+        u32::from_le_bytes([
+            contents[at],
+            contents[at + 1],
+            contents[at + 2],
+            contents[at + 3],
+        ]) as usize
+    };
+
+    if contents.len() < 20 {
+        return Err(VoxError::InvalidFile(String::from("MAIN chunk is cut off")));
+    }
+    //skips the header and the MAIN chunk header
+    let mut current_pos: usize = 20;
+    let mut previous = String::new();
+    while current_pos < contents.len() {
+        if current_pos + header_size > contents.len() {
+            return Err(VoxError::InvalidFile(format!(
+                "chunk at byte {} is cut off",
+                current_pos
+            )));
+        }
+        let name = String::from_utf8_lossy(&contents[current_pos..current_pos + 4]).into_owned();
+        let content_size = read_u32(current_pos + 4);
+        let end = (current_pos + header_size)
+            .checked_add(content_size)
+            .and_then(|end| end.checked_add(read_u32(current_pos + 8)))
+            .filter(|end| *end <= contents.len())
+            .ok_or_else(|| {
+                VoxError::InvalidFile(format!(
+                    "{} chunk at byte {} goes past the end of the file",
+                    name, current_pos
+                ))
+            })?;
+
+        let content = current_pos + header_size;
+        let too_small = match name.as_str() {
+            "SIZE" => content_size < 12,
+            "XYZI" => content_size < 4 || (content_size - 4) / 4 < read_u32(content),
+            "RGBA" => content_size < 1024,
+            _ => false,
+        };
+        if too_small {
+            return Err(VoxError::InvalidFile(format!(
+                "{} chunk at byte {} is too small",
+                name, current_pos
+            )));
+        }
+        //the voxels of a model are read from right after its size
+        if (name == "XYZI") != (previous == "SIZE") {
+            return Err(VoxError::InvalidFile(format!(
+                "SIZE and XYZI chunks are not in pairs at byte {}",
+                current_pos
+            )));
+        }
+
+        previous = name;
+        current_pos = end;
+    }
+    if previous == "SIZE" {
+        return Err(VoxError::InvalidFile(String::from(
+            "the last SIZE chunk does not have an XYZI chunk",
+        )));
     }
+    Ok(())
 }
@@ -1,41 +1,81 @@
 use crate::copy::ModelCopy;
 use crate::layer::Layer;
+use crate::material::Material;
 use crate::model::Model;
-use crate::node::{Node, NodeAttributes, NodeType, Transform};
-use crate::Color;
+use crate::node::{Node, NodeAttributes, NodeType, ShapeModel, Transform};
+use crate::riff::{Rotation, CHUNK_HEADER_SIZE};
+use crate::writing::DEFAULT_BUFFER_SIZE;
+use crate::{Axis, Color, VoxError};
+use std::path::Path;
 
 /// Struct which holds all data for a .vox file such as models and palette
 pub struct VoxFile {
     pub models: Vec<Model>,
     pub palette: [Color; 256],
     pub root_node: Node,
-    pub(crate) layers: Vec<Layer>,
+    pub layers: Vec<Layer>,
     pub copies: Vec<ModelCopy>,
+    pub materials: Vec<Material>,
+}
+
+//the models of the clone get new ids in the order they are in so they match the order they are written in
+impl Clone for VoxFile {
+    fn clone(&self) -> VoxFile {
+        let mut voxfile = VoxFile {
+            models: self.models.clone(),
+            palette: self.palette,
+            root_node: self.root_node.clone(),
+            layers: self.layers.clone(),
+            copies: self.copies.clone(),
+            materials: self.materials.clone(),
+        };
+        voxfile.renumber_models();
+        voxfile.make_nodes();
+
+        voxfile
+    }
 }
 
 impl VoxFile {
-    //size in bytes when written
-    pub(in crate::voxfile) fn get_size(&self) -> i32 {
-        let mut size = 1024;
+    //size in bytes of all the chunks inside of the MAIN chunk when written
+    pub(in crate::voxfile) fn get_size(&self) -> u64 {
+        //palette
+        let mut size = CHUNK_HEADER_SIZE + 1024;
         for model in self.models.iter() {
             size += model.get_size();
         }
         for layer in self.layers.iter() {
-            size += layer.get_size()
+            size += CHUNK_HEADER_SIZE + layer.get_size() as u64;
+        }
+        for material in self.materials.iter() {
+            size += CHUNK_HEADER_SIZE + material.get_size() as u64;
         }
-        size += self.root_node.get_all_size();
+        let num_of_nodes = self.root_node.num_children() as u64 + 1;
+        size += num_of_nodes * CHUNK_HEADER_SIZE + self.root_node.get_all_size() as u64;
         size
     }
 
-    pub(in crate::voxfile) fn make_nodes(&mut self) {
+    pub(crate) fn make_nodes(&mut self) {
         let mut root_node = Node::new(
             NodeType::Transform(Transform::default()),
             NodeAttributes::new(),
         );
         let mut group_node = Node::new(NodeType::Group, NodeAttributes::new());
 
+        //models used as keyframes of an animated model are shown through that model
+        let mut frame_ids = Vec::new();
         for model in self.models.iter() {
-            group_node.add_child(model.to_node());
+            for frame in model.frames.iter() {
+                if frame.model_id != model.id {
+                    frame_ids.push(frame.model_id);
+                }
+            }
+        }
+
+        for model in self.models.iter() {
+            if !frame_ids.contains(&model.id) {
+                group_node.add_child(model.to_node());
+            }
         }
 
         for copy in self.copies.iter() {
@@ -45,6 +85,49 @@ impl VoxFile {
         self.root_node = root_node
     }
 
+    //gives the models ids in the order they are in and updates everything that uses the old ids
+    pub(in crate::voxfile) fn renumber_models(&mut self) {
+        let old_ids: Vec<i32> = self.models.iter().map(|model| model.id).collect();
+        let new_id = |old_id: i32| {
+            old_ids
+                .iter()
+                .position(|id| *id == old_id)
+                .map_or(old_id, |index| index as i32)
+        };
+
+        for (index, model) in self.models.iter_mut().enumerate() {
+            model.id = index as i32;
+            for frame in model.frames.iter_mut() {
+                frame.model_id = new_id(frame.model_id);
+            }
+        }
+        for copy in self.copies.iter_mut() {
+            copy.model_id = new_id(copy.model_id);
+        }
+    }
+
+    //a model with the voxels and keyframes of the model a copy is of, where the copy is
+    pub(in crate::voxfile) fn copy_as_model(&self, copy: &ModelCopy, id: i32) -> Option<Model> {
+        let model = self.models.iter().find(|model| model.id == copy.model_id)?;
+        let mut copied = model.clone();
+        copied.position = copy.position;
+        copied.rotation = copy.rotation;
+        copied.layer = copy.layer;
+        copied.name = copy.name.clone();
+        copied.id = id;
+        for frame in copied.frames.iter_mut() {
+            if frame.model_id == model.id {
+                frame.model_id = id;
+            }
+        }
+        Some(copied)
+    }
+
+    //an id that no model has
+    pub(in crate::voxfile) fn unused_model_id(&self) -> i32 {
+        self.models.iter().map(|model| model.id).max().unwrap_or(-1) + 1
+    }
+
     //takes data from nodes and applies it to models
     pub(crate) fn get_node_data(&mut self) {
         let mut used_model_ids = Vec::new();
@@ -70,21 +153,21 @@ impl VoxFile {
 
         match transform_node.node_type {
             NodeType::Transform(ref trans) => {
-                pos = trans.translation;
+                //only the first frame is used for the model
+                let frame = trans.frames.first().cloned().unwrap_or_default();
+                pos = frame.translation;
                 layer = Some(trans.layer);
-                rot = match trans.rotation {
-                    None => None,
-                    Some(rot) => Some(rot as u8),
-                };
+                rot = frame.rotation.map(|rot| rot as u8);
             }
             _ => return None,
         }
 
         if transform_node.has_child_shape() {
             match transform_node.children[0].node_type {
-                NodeType::Shape(shape_id) => {
-                    id = shape_id;
-                }
+                NodeType::Shape(ref models) => match models.first() {
+                    Some(model) => id = model.model_id,
+                    None => return None,
+                },
                 _ => return None,
             }
         } else {
@@ -115,6 +198,15 @@ impl VoxFile {
         }
     }
 
+    //sets the keyframes of an animated model
+    pub(crate) fn change_model_frames(&mut self, id: i32, frames: Vec<ShapeModel>) {
+        for model in self.models.iter_mut() {
+            if model.id == id {
+                model.frames = frames.clone();
+            }
+        }
+    }
+
     pub(crate) fn add_copy(
         &mut self,
         id: i32,
@@ -132,7 +224,8 @@ impl VoxFile {
         })
     }
 
-    /// creates a new voxfile with one model with the size given.
+    /// creates a new voxfile with one model with the size given. The palette starts as the default
+    /// MagicaVoxel palette.
     ///
     /// # Example
     /// ```
@@ -147,20 +240,64 @@ impl VoxFile {
         }
         VoxFile {
             models: vec![Model::new(size_x, size_y, size_z)],
-            palette: [Color {
-                r: 75,
-                g: 75,
-                b: 75,
-                a: 255,
-            }; 256],
+            palette: Color::default_palette(),
             root_node: Node::new(NodeType::Group, NodeAttributes::new()),
             layers: vec![],
             copies: vec![],
+            materials: vec![],
         }
     }
 
-    pub fn save(&mut self, file_path: &str) {
-        self.write(file_path);
+    /// Saves the voxfile. It will return an error if the file can not be created or if the voxfile
+    /// is too large to be written.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let path = std::env::temp_dir().join("save.vox");
+    /// vox.save(&path).unwrap();
+    /// ```
+    pub fn save(&mut self, file_path: impl AsRef<Path>) -> Result<(), VoxError> {
+        self.write(file_path.as_ref(), false, DEFAULT_BUFFER_SIZE)
+    }
+
+    /// Like save() but writes the voxels of every model sorted by (z, y, x) instead of in the order
+    /// they were added. Models with the same voxels will always produce the same bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_voxel_at_pos(5, 2, 1, 1).unwrap();
+    /// vox.models[0].add_voxel_at_pos(1, 2, 1, 1).unwrap();
+    /// let path = std::env::temp_dir().join("sorted.vox");
+    /// vox.save_sorted(&path).unwrap();
+    /// ```
+    pub fn save_sorted(&mut self, file_path: impl AsRef<Path>) -> Result<(), VoxError> {
+        self.write(file_path.as_ref(), true, DEFAULT_BUFFER_SIZE)
+    }
+
+    /// Like save() but writes through a buffer with the number of bytes given instead of the default of 8
+    /// KiB. A larger buffer can make writing large files faster.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_cube(0, 0, 0, 10, 10, 10, 1).unwrap();
+    /// let path = std::env::temp_dir().join("buffered.vox");
+    /// vox.save_with_buffer_size(&path, 1024 * 1024).unwrap();
+    /// ```
+    pub fn save_with_buffer_size(
+        &mut self,
+        file_path: impl AsRef<Path>,
+        bytes: usize,
+    ) -> Result<(), VoxError> {
+        self.write(file_path.as_ref(), false, bytes)
     }
 
     /// Add a copy of a model at a certain position. The model id is which model in the array of models to use.
@@ -176,6 +313,82 @@ impl VoxFile {
         self.add_copy(model_id, Some((x, y, z)), None, None, None);
     }
 
+    /// Animates a model by cycling the colors in a range of the palette. Every frame shifts the
+    /// color of the voxels in the range by one more index, wrapping around at the end of the range.
+    /// A model is added for each frame after the first, which is the model itself.
+    ///
+    /// Returns an error if there is no model at `model_index`, if `frames` is 0, or if the range
+    /// does not go from a start between 1 and 255 up to an end that is not before it. A `frames` of 0 is
+    /// returned as `VoxError::InvalidRange { start: 1, end: 0 }`, the empty range of frames 1 to 0.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_voxel_at_pos(1, 1, 1, 2).unwrap();
+    ///
+    /// // cycles colors 1 to 3 over 3 frames
+    /// vox.add_palette_cycle_animation(0, 3, (1, 3)).unwrap();
+    /// assert_eq!(3, vox.models.len());
+    /// assert_eq!(3, vox.models[1].voxels[0].color_index);
+    /// assert_eq!(1, vox.models[2].voxels[0].color_index);
+    /// ```
+    pub fn add_palette_cycle_animation(
+        &mut self,
+        model_index: usize,
+        frames: u8,
+        range: (u8, u8),
+    ) -> Result<(), VoxError> {
+        if model_index >= self.models.len() {
+            return Err(VoxError::InvalidModelIndex(model_index));
+        }
+        if frames == 0 {
+            return Err(VoxError::InvalidRange { start: 1, end: 0 });
+        }
+        if range.0 == 0 {
+            return Err(VoxError::InvalidColorIndex(0));
+        }
+        if range.0 > range.1 {
+            return Err(VoxError::InvalidRange {
+                start: range.0,
+                end: range.1,
+            });
+        }
+        let range_len = (range.1 - range.0) as u16 + 1;
+
+        let base = &self.models[model_index];
+        let base_id = base.id;
+        let mut frame_models = Vec::new();
+        for frame in 1..frames {
+            let mut frame_model = Model::new(base.size.0, base.size.1, base.size.2);
+            frame_model.voxels = base.voxels.clone();
+            frame_model.change_voxels(|voxel| {
+                if voxel.color_index >= range.0 && voxel.color_index <= range.1 {
+                    let shifted = (voxel.color_index - range.0) as u16 + frame as u16;
+                    voxel.color_index = range.0 + (shifted % range_len) as u8;
+                }
+            });
+            frame_models.push(frame_model);
+        }
+
+        let mut shape_models = vec![ShapeModel {
+            model_id: base_id,
+            frame: Some(0),
+        }];
+        for (frame, frame_model) in frame_models.into_iter().enumerate() {
+            self.add_model(frame_model);
+            shape_models.push(ShapeModel {
+                model_id: self.models[self.models.len() - 1].id,
+                frame: Some(frame as i32 + 1),
+            });
+        }
+
+        self.models[model_index].frames = shape_models;
+
+        Ok(())
+    }
+
     /// Creates a new layer and returns the id that it has.
     ///
     /// # Example
@@ -186,9 +399,15 @@ impl VoxFile {
     /// vox.models[0].layer = Some(vox.add_layer(String::from("my layer"), false));
     /// ```
     pub fn add_layer(&mut self, name: String, hidden: bool) -> i32 {
-        self.layers
-            .push(Layer::new(name, hidden, self.layers.len() as i32));
-        self.layers.len() as i32
+        //layers read from a file might not have ids that match their position
+        let id = self
+            .layers
+            .iter()
+            .map(|layer| layer.id() + 1)
+            .max()
+            .unwrap_or(0);
+        self.layers.push(Layer::new(name, hidden, id));
+        id
     }
 
     /// Changes the id of a model in the voxfile. If another model already has that id it will panic.
@@ -250,4 +469,346 @@ impl VoxFile {
 
         return None;
     }
+
+    /// Gets the first model with the name given.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].set_name("tree");
+    /// assert!(vox.model_by_name("tree").is_some());
+    /// assert!(vox.model_by_name("rock").is_none());
+    /// ```
+    pub fn model_by_name(&self, name: &str) -> Option<&Model> {
+        self.models
+            .iter()
+            .find(|model| model.name.as_deref() == Some(name))
+    }
+
+    /// Gets the first model with the name given so it can be changed.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].set_name("tree");
+    /// vox.model_by_name_mut("tree").unwrap().add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    /// assert_eq!(1, vox.models[0].num_of_voxels());
+    /// ```
+    pub fn model_by_name_mut(&mut self, name: &str) -> Option<&mut Model> {
+        self.models
+            .iter_mut()
+            .find(|model| model.name.as_deref() == Some(name))
+    }
+
+    /// Removes all of the models without voxels and returns how many were removed. Copies of the removed
+    /// models are removed too and the models left get new ids in the order they are in.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{VoxFile, Model};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// let mut model = Model::new(10, 10, 10);
+    /// model.add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    /// vox.add_model(model);
+    ///
+    /// assert_eq!(1, vox.remove_empty_models());
+    /// assert_eq!(1, vox.models.len());
+    /// assert_eq!(1, vox.models[0].num_of_voxels());
+    /// assert_eq!(0, vox.models[0].get_id());
+    /// ```
+    pub fn remove_empty_models(&mut self) -> usize {
+        let removed_ids: Vec<i32> = self
+            .models
+            .iter()
+            .filter(|model| model.is_empty())
+            .map(|model| model.id)
+            .collect();
+
+        self.models.retain(|model| !model.is_empty());
+        self.copies
+            .retain(|copy| !removed_ids.contains(&copy.model_id));
+        for model in self.models.iter_mut() {
+            model
+                .frames
+                .retain(|frame| !removed_ids.contains(&frame.model_id));
+            if model.frames.len() == 1 {
+                model.frames.clear();
+            }
+        }
+
+        self.renumber_models();
+        self.make_nodes();
+        removed_ids.len()
+    }
+
+    /// Replaces all of the models on a layer with one model that has all of their voxels in the same place.
+    /// The position of a model is the center of the model like in MagicaVoxel, and models without a
+    /// position or layer are at (0, 0, 0) on layer 0. Where models overlap, the voxel of the later model is
+    /// used. Copies on the layer are merged in too, even when the model they copy is on a different layer,
+    /// and copies on other layers of the merged models are turned into models of their own so they stay in
+    /// the scene. Rotation is not applied, so it will return `VoxError::RotatedModel` if a model or copy on
+    /// the layer is rotated. It will return an error and leave the models as they are if anything is rotated
+    /// or the new model would be larger than 256 on any axis.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(2, 2, 2);
+    /// vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    /// vox.models[0].layer = Some(3);
+    ///
+    /// let mut model = Model::new(2, 2, 2);
+    /// model.add_voxel_at_pos(0, 0, 0, 2).unwrap();
+    /// model.position = Some((10, 0, 0));
+    /// model.layer = Some(3);
+    /// vox.add_model(model);
+    ///
+    /// vox.merge_layer(3).unwrap();
+    /// assert_eq!(1, vox.models.len());
+    /// assert_eq!((12, 2, 2), vox.models[0].size);
+    /// assert_eq!(2, vox.models[0].num_of_voxels());
+    /// assert_eq!(Some((5, 0, 0)), vox.models[0].position);
+    /// ```
+    pub fn merge_layer(&mut self, layer: i32) -> Result<(), VoxError> {
+        let on_layer = |layer_id: Option<i32>| layer_id.unwrap_or(0) == layer;
+        //the (0, 0, 0) corner of a model in the scene
+        let corner = |model: &Model, position: Option<(i32, i32, i32)>| {
+            let position = position.unwrap_or((0, 0, 0));
+            [
+                position.0 as i64 - model.size.0 as i64 / 2,
+                position.1 as i64 - model.size.1 as i64 / 2,
+                position.2 as i64 - model.size.2 as i64 / 2,
+            ]
+        };
+
+        let first_index = match self.models.iter().position(|model| on_layer(model.layer)) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+
+        //the models and copies on the layer, with the position they are at
+        let mut parts: Vec<_> = self
+            .models
+            .iter()
+            .filter(|model| on_layer(model.layer))
+            .map(|model| (model, model.position, model.rotation))
+            .collect();
+        for copy in self.copies.iter().filter(|copy| on_layer(copy.layer)) {
+            if let Some(model) = self.models.iter().find(|model| model.id == copy.model_id) {
+                parts.push((model, copy.position, copy.rotation));
+            }
+        }
+        for (_, _, rotation) in parts.iter() {
+            if let Some(rotation) = *rotation {
+                if rotation != Rotation::IDENTITY.to_byte() {
+                    return Err(VoxError::RotatedModel(rotation));
+                }
+            }
+        }
+
+        let mut min = [i64::MAX; 3];
+        let mut max = [i64::MIN; 3];
+        for (model, position, _) in parts.iter() {
+            let start = corner(model, *position);
+            let size = [model.size.0, model.size.1, model.size.2];
+            for axis in 0..3 {
+                min[axis] = min[axis].min(start[axis]);
+                max[axis] = max[axis].max(start[axis] + size[axis] as i64);
+            }
+        }
+
+        let mut size = [0u16; 3];
+        for (axis, side) in size.iter_mut().enumerate() {
+            let value = max[axis] - min[axis];
+            if value > 256 {
+                return Err(VoxError::SizeTooLarge {
+                    axis: [Axis::X, Axis::Y, Axis::Z][axis],
+                    value: value.min(u32::MAX as i64) as u32,
+                });
+            }
+            *side = value as u16;
+        }
+
+        let mut merged = Model::new(size[0], size[1], size[2]);
+        merged.layer = Some(layer);
+        merged.position = Some((
+            (min[0] + size[0] as i64 / 2) as i32,
+            (min[1] + size[1] as i64 / 2) as i32,
+            (min[2] + size[2] as i64 / 2) as i32,
+        ));
+        for (model, position, _) in parts.iter() {
+            let start = corner(model, *position);
+            let at = (
+                (start[0] - min[0]) as u8,
+                (start[1] - min[1]) as u8,
+                (start[2] - min[2]) as u8,
+            );
+            merged.merge_with(model, at, |_, added| added)?;
+        }
+
+        let removed_ids: Vec<i32> = self
+            .models
+            .iter()
+            .filter(|model| on_layer(model.layer))
+            .map(|model| model.id)
+            .collect();
+        //copies on other layers of the removed models become models of their own
+        let mut kept_copies: Vec<Model> = Vec::new();
+        for copy in self.copies.iter().filter(|copy| !on_layer(copy.layer)) {
+            if !removed_ids.contains(&copy.model_id) {
+                continue;
+            }
+            let id = self.unused_model_id() + kept_copies.len() as i32;
+            kept_copies.extend(self.copy_as_model(copy, id));
+        }
+        self.models.retain(|model| !on_layer(model.layer));
+        self.models.extend(kept_copies);
+        self.copies
+            .retain(|copy| !on_layer(copy.layer) && !removed_ids.contains(&copy.model_id));
+        for model in self.models.iter_mut() {
+            model
+                .frames
+                .retain(|frame| !removed_ids.contains(&frame.model_id));
+            if model.frames.len() == 1 {
+                model.frames.clear();
+            }
+        }
+        //the new id is given when renumbering
+        merged.id = -1;
+        self.models.insert(first_index, merged);
+
+        self.renumber_models();
+        self.make_nodes();
+        Ok(())
+    }
+
+    /// Makes a voxfile for each layer with the models on that layer. The files are in the same order as the
+    /// layers, followed by files for layers that models use but that are not in the layers of the voxfile,
+    /// and models and copies without a layer are on layer 0. Every file has the same palette and materials.
+    /// A copy on the same layer as its model stays a copy, and a copy on a different layer is made into a
+    /// model in the file of its own layer. Models used as frames of an animated model go with that model.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].layer = Some(vox.add_layer(String::from("ground"), false));
+    ///
+    /// let mut tree = Model::new(5, 5, 5);
+    /// tree.layer = Some(vox.add_layer(String::from("trees"), false));
+    /// vox.add_model(tree);
+    ///
+    /// let files = vox.split_by_layer();
+    /// assert_eq!(2, files.len());
+    /// assert_eq!((5, 5, 5), files[1].models[0].size);
+    /// ```
+    pub fn split_by_layer(&self) -> Vec<VoxFile> {
+        let layer_of = |model: &Model| model.layer.unwrap_or(0);
+        let mut layer_ids: Vec<i32> = self.layers.iter().map(|layer| layer.id()).collect();
+        let mut other_ids: Vec<i32> = self
+            .models
+            .iter()
+            .map(layer_of)
+            .chain(self.copies.iter().map(|copy| copy.layer.unwrap_or(0)))
+            .filter(|id| !layer_ids.contains(id))
+            .collect();
+        other_ids.sort_unstable();
+        other_ids.dedup();
+        layer_ids.extend(other_ids);
+
+        layer_ids
+            .iter()
+            .map(|&layer_id| {
+                let mut model_ids: Vec<i32> = Vec::new();
+                for model in self
+                    .models
+                    .iter()
+                    .filter(|model| layer_of(model) == layer_id)
+                {
+                    model_ids.push(model.id);
+                    model_ids.extend(model.frames.iter().map(|frame| frame.model_id));
+                }
+                let layer_model_ids = model_ids.clone();
+                //copies of models on other layers, with the frames of the models they copy
+                let mut copied: Vec<Model> = Vec::new();
+                for copy in self.copies.iter().filter(|copy| {
+                    copy.layer.unwrap_or(0) == layer_id && !layer_model_ids.contains(&copy.model_id)
+                }) {
+                    let id = self.unused_model_id() + copied.len() as i32;
+                    if let Some(model) = self.copy_as_model(copy, id) {
+                        model_ids.extend(
+                            model
+                                .frames
+                                .iter()
+                                .map(|frame| frame.model_id)
+                                .filter(|frame_id| *frame_id != id),
+                        );
+                        copied.push(model);
+                    }
+                }
+
+                let mut models: Vec<Model> = self
+                    .models
+                    .iter()
+                    .filter(|model| model_ids.contains(&model.id))
+                    .cloned()
+                    .collect();
+                models.extend(copied);
+                let mut voxfile = VoxFile {
+                    models,
+                    palette: self.palette,
+                    root_node: Node::new(NodeType::Group, NodeAttributes::new()),
+                    layers: self
+                        .layers
+                        .iter()
+                        .filter(|layer| layer.id() == layer_id)
+                        .cloned()
+                        .collect(),
+                    copies: self
+                        .copies
+                        .iter()
+                        .filter(|copy| {
+                            copy.layer.unwrap_or(0) == layer_id
+                                && layer_model_ids.contains(&copy.model_id)
+                        })
+                        .cloned()
+                        .collect(),
+                    materials: self.materials.clone(),
+                };
+                voxfile.renumber_models();
+                voxfile.make_nodes();
+                voxfile
+            })
+            .collect()
+    }
+
+    /// Puts every model and copy of a model on the layer. The layer does not need to be in the layers of
+    /// the voxfile.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::{Model, VoxFile};
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.add_model(Model::new(5, 5, 5));
+    /// let layer = vox.add_layer(String::from("imported"), false);
+    ///
+    /// vox.assign_all_to_layer(layer);
+    /// assert!(vox.models.iter().all(|model| model.layer == Some(layer)));
+    /// ```
+    pub fn assign_all_to_layer(&mut self, layer: i32) {
+        for model in self.models.iter_mut() {
+            model.layer = Some(layer);
+        }
+        for copy in self.copies.iter_mut() {
+            copy.layer = Some(layer);
+        }
+    }
 }
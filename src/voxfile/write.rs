@@ -1,30 +1,106 @@
-use crate::riff::write_chunk;
-use crate::voxfile::VoxFile;
-use crate::writing::{write_slice, write_string_literal};
+use crate::riff::{chunk_size, write_chunk};
+use crate::voxfile::{VoxFile, VERSION};
+use crate::writing::{write_atomically, write_slice, write_string_literal};
+use crate::VoxError;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{self, BufWriter};
+use std::path::Path;
 
 impl VoxFile {
-    pub(crate) fn write(&mut self, path: &str) {
+    pub(crate) fn write(
+        &mut self,
+        path: &Path,
+        sorted: bool,
+        buffer_size: usize,
+    ) -> Result<(), VoxError> {
         //setups nodes for all children
         self.make_nodes();
+        let size = self.get_size();
+        self.write_with_size(path, size, sorted, buffer_size)
+    }
+
+    fn write_with_size(
+        &mut self,
+        path: &Path,
+        size: u64,
+        sorted: bool,
+        buffer_size: usize,
+    ) -> Result<(), VoxError> {
+        //checked before creating the file so nothing is written if it is too large
+        let main_size = chunk_size(size)?;
+
+        //written next to the file and renamed over it so a failed save does not break the old file
+        write_atomically(path, buffer_size, |writer| {
+            self.write_contents(writer, main_size, sorted)?;
+            Ok(())
+        })
+    }
 
-        let file = File::create(path).expect("failed to create file");
-        let mut writer = BufWriter::new(file);
-        write_string_literal(&mut writer, "VOX ");
-        write_slice(&mut writer, &[0, 0, 0, 0]);
+    fn write_contents(
+        &mut self,
+        writer: &mut BufWriter<File>,
+        main_size: u32,
+        sorted: bool,
+    ) -> io::Result<()> {
+        write_string_literal(writer, "VOX ")?;
+        write_slice(writer, &VERSION.to_le_bytes())?;
 
-        write_chunk("MAIN", 0, self.get_size() as u32, &mut writer);
+        write_chunk("MAIN", 0, main_size, writer)?;
         for model in self.models.iter() {
-            model.write(&mut writer);
+            model.write(writer, sorted)?;
         }
-        self.root_node.write_all(&mut writer);
+        self.root_node.write_all(writer)?;
         for layer in self.layers.iter() {
-            layer.write(&mut writer);
+            layer.write(writer)?;
         }
-        write_chunk("RGBA", 1024, 0, &mut writer);
+        write_chunk("RGBA", 1024, 0, writer)?;
         for color in self.palette.iter() {
-            write_slice(&mut writer, &[color.r, color.g, color.b, color.a])
+            write_slice(writer, &[color.r, color.g, color.b, color.a])?;
         }
+        for material in self.materials.iter() {
+            material.write(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writing::{temp_path, DEFAULT_BUFFER_SIZE};
+
+    #[test]
+    fn oversized_file_is_not_written() {
+        let path = std::env::temp_dir().join("create_vox_oversized.vox");
+        let _ = std::fs::remove_file(&path);
+        let mut vox = VoxFile::new(4, 4, 4);
+        vox.make_nodes();
+
+        //a real file this large would need gigabytes of voxels
+        let result = vox.write_with_size(&path, u32::MAX as u64 + 1, false, DEFAULT_BUFFER_SIZE);
+        assert!(matches!(result, Err(VoxError::TooLarge(_))));
+        assert!(!path.exists());
+        assert!(!temp_path(&path).exists());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn write_errors_are_returned() {
+        //every write to /dev/full fails because the device has no space left
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open("/dev/full")
+            .unwrap();
+        let mut writer = BufWriter::with_capacity(16, file);
+        let mut vox = VoxFile::new(4, 4, 4);
+        vox.models[0].add_cube(0, 0, 0, 4, 4, 4, 1).unwrap();
+        vox.make_nodes();
+
+        let main_size = chunk_size(vox.get_size()).unwrap();
+        let error = vox
+            .write_contents(&mut writer, main_size, false)
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::StorageFull, error.kind());
     }
 }
@@ -0,0 +1,112 @@
+use crate::model::Model;
+use crate::{Color, VoxError};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// version 4 of the .vxm format used by Voxel Builder and VoxEdit. y is the up axis, so the z axis of the
+// model is written as the vxm y axis. all numbers are little endian.
+//
+// "VXM4"
+// u32 x3       size
+// f32 x3       pivot, from 0.0 to 1.0 on each axis
+// u8           number of materials
+// for each material: u8 blue, green, red, alpha, emissive
+// runs of (u8 length, u8 material) going over z, then y, then x of the file, ended by a length of 0.
+// material 255 is empty.
+
+const EMPTY: u8 = 255;
+
+impl Model {
+    /// Writes the model to a .vxm file, the format used by Voxel Builder and VoxEdit. Only the colors on the
+    /// palette that the model uses are saved. It will return `VoxError::Io` if the file can not be written.
+    ///
+    /// # Example
+    /// ```
+    /// use create_vox::VoxFile;
+    ///
+    /// let mut vox = VoxFile::new(10, 10, 10);
+    /// vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 1).unwrap();
+    ///
+    /// let path = std::env::temp_dir().join("cube.vxm");
+    /// vox.models[0].export_vxm(&vox.palette, &path).unwrap();
+    /// ```
+    pub fn export_vxm(
+        &self,
+        palette: &[Color; 256],
+        path: impl AsRef<Path>,
+    ) -> Result<(), VoxError> {
+        //x, y, and z of the file
+        let size = (
+            self.size.0 as usize,
+            self.size.2 as usize,
+            self.size.1 as usize,
+        );
+
+        //materials are numbered in the order of the color indexes. color index 0 is not a color, so there
+        //are at most 255 materials and 255 is free for empty
+        let mut materials = [EMPTY; 256];
+        let mut used: Vec<u8> = self
+            .voxels
+            .iter()
+            .map(|voxel| voxel.color_index)
+            .filter(|index| *index != 0)
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+        for (material, index) in used.iter().enumerate() {
+            materials[*index as usize] = material as u8;
+        }
+
+        let mut cells = vec![EMPTY; size.0 * size.1 * size.2];
+        for voxel in self.voxels.iter() {
+            let (x, y, z) = (
+                voxel.position.0 as usize,
+                voxel.position.2 as usize,
+                voxel.position.1 as usize,
+            );
+            if x < size.0 && y < size.1 && z < size.2 {
+                cells[x * size.1 * size.2 + y * size.2 + z] = materials[voxel.color_index as usize];
+            }
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(b"VXM4")?;
+        for side in [size.0, size.1, size.2].iter() {
+            writer.write_all(&(*side as u32).to_le_bytes())?;
+        }
+        for _ in 0..3 {
+            writer.write_all(&0.5f32.to_le_bytes())?;
+        }
+
+        writer.write_all(&[used.len() as u8])?;
+        for index in used.iter() {
+            let color = palette[*index as usize - 1];
+            writer.write_all(&[color.b, color.g, color.r, color.a, 0])?;
+        }
+
+        //(length, material) pairs where a run can be up to 255 long
+        let mut data = Vec::new();
+        let mut cells = cells.iter();
+        if let Some(&first) = cells.next() {
+            let mut material = first;
+            let mut length: u8 = 1;
+            for &cell in cells {
+                if cell == material && length < 255 {
+                    length += 1;
+                } else {
+                    data.push(length);
+                    data.push(material);
+                    material = cell;
+                    length = 1;
+                }
+            }
+            data.push(length);
+            data.push(material);
+        }
+        data.push(0);
+        writer.write_all(&data)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -1,12 +1,81 @@
-use std::io::Write;
+use crate::VoxError;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
 
 pub(crate) fn write_string_literal(
     inputfile: &mut std::io::BufWriter<std::fs::File>,
     string: &str,
-) {
-    inputfile.write(string.as_bytes()).expect("failed");
+) -> io::Result<()> {
+    inputfile.write_all(string.as_bytes())
 }
 
-pub(crate) fn write_slice(inputfile: &mut std::io::BufWriter<std::fs::File>, slice: &[u8]) {
-    inputfile.write(slice).expect("failed");
+pub(crate) fn write_slice(
+    inputfile: &mut std::io::BufWriter<std::fs::File>,
+    slice: &[u8],
+) -> io::Result<()> {
+    inputfile.write_all(slice)
+}
+
+//the same size BufWriter::new() uses
+pub(crate) const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+//writes to a temporary file in the same folder and renames it to path once everything is written, so a
+//write that fails part way through does not leave a broken file at path
+pub(crate) fn write_atomically<F>(path: &Path, buffer_size: usize, write: F) -> Result<(), VoxError>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<(), VoxError>,
+{
+    let temp_path = temp_path(path);
+    let write_temp = || -> Result<(), VoxError> {
+        let mut writer = BufWriter::with_capacity(buffer_size, File::create(&temp_path)?);
+        write(&mut writer)?;
+        let file = writer.into_inner().map_err(io::Error::from)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    };
+
+    let result = write_temp();
+    if result.is_err() {
+        //the temporary file might not have been made, and the original error is more useful
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+//hidden file next to path, for example "models/.tree.vox.tmp" for "models/tree.vox"
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_write_keeps_original_file() {
+        let path = std::env::temp_dir().join("failed_write_keeps_original_file.vox");
+        fs::write(&path, b"original").unwrap();
+
+        let result = write_atomically(&path, DEFAULT_BUFFER_SIZE, |writer| {
+            write_slice(writer, b"half written")?;
+            Err(VoxError::InvalidFile(String::from("simulated failure")))
+        });
+        assert!(matches!(result, Err(VoxError::InvalidFile(_))));
+        assert_eq!(b"original".to_vec(), fs::read(&path).unwrap());
+        assert!(!temp_path(&path).exists());
+
+        write_atomically(&path, DEFAULT_BUFFER_SIZE, |writer| {
+            write_slice(writer, b"replaced")?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(b"replaced".to_vec(), fs::read(&path).unwrap());
+        assert!(!temp_path(&path).exists());
+    }
 }
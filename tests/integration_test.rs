@@ -1,4 +1,4 @@
-use create_vox::{Color, VoxFile, Voxel};
+use create_vox::{Color, Model, VoxFile, Voxel};
 #[test]
 //#[ignore]
 fn it_works() {
@@ -8,7 +8,7 @@ fn it_works() {
     vox.add_model_copy(0, 10, 10, 10);
     vox.add_layer(String::from("cool layer"), false);
     vox.models[0].layer = Some(1);
-    vox.save("tester.vox");
+    vox.save("tester.vox").unwrap();
 }
 
 #[test]
@@ -20,6 +20,1855 @@ fn size_too_big() {
 #[test]
 fn voxel_at_pos() {
     let mut test_vox = VoxFile::new(10, 10, 10);
-    test_vox.models[0].add_voxel_at_pos(3, 4, 2, 1);
-    assert_eq!(true, test_vox.models[0].is_voxel_at_pos(3, 4, 2));
+    test_vox.models[0].add_voxel_at_pos(3, 4, 2, 1).unwrap();
+    assert!(test_vox.models[0].is_voxel_at_pos(3, 4, 2));
+}
+
+#[test]
+fn sorted_save_is_reproducible() {
+    let mut first = VoxFile::new(10, 10, 10);
+    first.models[0].add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    first.models[0].add_voxel_at_pos(4, 0, 1, 2).unwrap();
+    first.models[0].add_voxel_at_pos(0, 5, 1, 3).unwrap();
+
+    let mut second = VoxFile::new(10, 10, 10);
+    second.models[0].add_voxel_at_pos(0, 5, 1, 3).unwrap();
+    second.models[0].add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    second.models[0].add_voxel_at_pos(4, 0, 1, 2).unwrap();
+
+    let first_path = std::env::temp_dir().join("create_vox_sorted_first.vox");
+    let second_path = std::env::temp_dir().join("create_vox_sorted_second.vox");
+    first.save_sorted(first_path.to_str().unwrap()).unwrap();
+    second.save_sorted(second_path.to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        std::fs::read(&first_path).unwrap(),
+        std::fs::read(&second_path).unwrap()
+    );
+}
+
+#[test]
+fn palette_cycle_animation_round_trip() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 4).unwrap();
+    vox.models[0].add_voxel_at_pos(2, 1, 1, 6).unwrap();
+    vox.models[0].add_voxel_at_pos(3, 1, 1, 9).unwrap();
+    vox.add_palette_cycle_animation(0, 3, (4, 6)).unwrap();
+
+    let path = std::env::temp_dir().join("create_vox_palette_cycle.vox");
+    vox.save(path.to_str().unwrap()).unwrap();
+    let loaded = VoxFile::load(path.to_str().unwrap());
+
+    assert_eq!(3, loaded.models.len());
+    assert_eq!(vec![0, 1, 2], loaded.models[0].get_frame_ids());
+    for (frame, expected) in [[4, 6, 9], [5, 4, 9], [6, 5, 9]].iter().enumerate() {
+        let colors: Vec<u8> = loaded.models[frame]
+            .voxels
+            .iter()
+            .map(|voxel| voxel.color_index)
+            .collect();
+        assert_eq!(expected.to_vec(), colors);
+    }
+}
+
+#[test]
+fn palette_cycle_animation_rejects_bad_arguments() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    assert!(matches!(
+        vox.add_palette_cycle_animation(1, 3, (4, 6)),
+        Err(create_vox::VoxError::InvalidModelIndex(1))
+    ));
+    assert!(matches!(
+        vox.add_palette_cycle_animation(0, 0, (4, 6)),
+        Err(create_vox::VoxError::InvalidRange { .. })
+    ));
+    assert!(matches!(
+        vox.add_palette_cycle_animation(0, 3, (0, 6)),
+        Err(create_vox::VoxError::InvalidColorIndex(0))
+    ));
+    assert!(matches!(
+        vox.add_palette_cycle_animation(0, 3, (6, 4)),
+        Err(create_vox::VoxError::InvalidRange { start: 6, end: 4 })
+    ));
+    assert_eq!(1, vox.models.len());
+}
+
+#[test]
+fn debug_format() {
+    assert_eq!("(3,4,5):7", format!("{:?}", Voxel::new(3, 4, 5, 7)));
+
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_cube(0, 0, 0, 2, 2, 2, 1).unwrap();
+    assert!(format!("{:?}", vox.models[0]).contains("num_of_voxels: 8"));
+}
+
+#[test]
+fn clone_is_independent() {
+    let mut original = VoxFile::new(10, 10, 10);
+    original.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    original.set_palette_color(1, 255, 0, 0, 255);
+    original.change_model_id(0, 12);
+    original.add_model_copy(12, 20, 0, 0);
+
+    let mut clone = original.clone();
+    assert_eq!(0, clone.models[0].get_id());
+    assert_eq!(0, clone.copies[0].model_id);
+
+    clone.models[0].add_voxel_at_pos(2, 2, 2, 1).unwrap();
+    clone.set_palette_color(1, 0, 255, 0, 255);
+
+    assert_eq!(1, original.models[0].num_of_voxels());
+    assert_eq!(12, original.models[0].get_id());
+    assert_eq!(Color::new(255, 0, 0, 255), original.get_palette_color(1));
+}
+
+#[test]
+fn binvox_export() {
+    let mut vox = VoxFile::new(10, 6, 4);
+    vox.models[0].add_cube(0, 0, 0, 3, 3, 3, 1).unwrap();
+    vox.models[0].add_voxel_at_pos(9, 5, 3, 2).unwrap();
+
+    let path = std::env::temp_dir().join("create_vox_export.binvox");
+    vox.models[0].export_binvox(path.to_str().unwrap()).unwrap();
+    let contents = std::fs::read(&path).unwrap();
+
+    let header_end = contents
+        .windows(5)
+        .position(|window| window == b"data\n")
+        .unwrap()
+        + 5;
+    let header = String::from_utf8(contents[..header_end].to_vec()).unwrap();
+    assert!(header.starts_with("#binvox 1\n"));
+    assert!(header.contains("dim 10 6 4\n"));
+    assert!(header.contains("translate 0 0 0\n"));
+    assert!(header.contains("scale 10\n"));
+
+    let mut cells = 0;
+    let mut filled = 0;
+    for pair in contents[header_end..].chunks(2) {
+        cells += pair[1] as u32;
+        if pair[0] == 1 {
+            filled += pair[1] as u32;
+        }
+    }
+    assert_eq!(10 * 6 * 4, cells);
+    assert_eq!(28, filled);
+}
+
+#[test]
+fn binvox_import() {
+    //2 by 2 by 2 grid with the first 3 cells filled
+    let mut contents = b"#binvox 1\ndim 2 2 2\ntranslate 0 0 0\nscale 2\ndata\n".to_vec();
+    contents.extend_from_slice(&[1, 3, 0, 5]);
+    let path = std::env::temp_dir().join("create_vox_import.binvox");
+    std::fs::write(&path, contents).unwrap();
+
+    let model = Model::from_binvox(path.to_str().unwrap(), 4).unwrap();
+    assert_eq!((2, 2, 2), model.size);
+    assert_eq!(3, model.num_of_voxels());
+    assert!(model.is_voxel_at_pos(0, 0, 0));
+    assert!(model.is_voxel_at_pos(0, 0, 1));
+    assert!(model.is_voxel_at_pos(0, 1, 0));
+    assert!(model.voxels.iter().all(|voxel| voxel.color_index == 4));
+}
+
+#[test]
+fn main_chunk_size_matches_file() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_cube(0, 0, 0, 5, 5, 5, 2).unwrap();
+    vox.add_model(Model::new(4, 4, 4));
+    vox.add_model_copy(0, 10, 10, 10);
+    vox.add_layer(String::from("layer"), true);
+
+    let path = std::env::temp_dir().join("create_vox_main_size.vox");
+    vox.save(path.to_str().unwrap()).unwrap();
+    let contents = std::fs::read(&path).unwrap();
+
+    let children_size =
+        u32::from_le_bytes([contents[16], contents[17], contents[18], contents[19]]);
+    assert_eq!(contents.len() - 20, children_size as usize);
+}
+
+#[test]
+fn occupancy_map_does_not_rescan() {
+    let mut vox = VoxFile::new(50, 50, 50);
+    vox.models[0].add_cube(0, 0, 0, 50, 50, 50, 1).unwrap();
+    let occupancy = vox.models[0].occupancy_map();
+
+    //the map is not tied to the model so lookups can not be going through its voxels
+    vox.models[0].clear_voxels();
+
+    //every voxel checked against 125,000 voxels would take far too long if each lookup scanned
+    let mut found = 0;
+    for x in 0..50 {
+        for y in 0..50 {
+            for z in 0..50 {
+                if occupancy.contains((x, y, z)) {
+                    found += 1;
+                }
+            }
+        }
+    }
+    assert_eq!(125_000, found);
+    assert_eq!(125_000, occupancy.len());
+    assert!(!occupancy.contains((50, 0, 0)));
+}
+
+#[test]
+fn mirror_copy_doubles_model() {
+    let mut vox = VoxFile::new(128, 3, 3);
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    vox.models[0].add_voxel_at_pos(127, 2, 1, 2).unwrap();
+
+    vox.models[0].mirror_copy(create_vox::Axis::X).unwrap();
+    assert_eq!((256, 3, 3), vox.models[0].size);
+    assert!(vox.models[0].is_voxel_at_pos(255, 0, 0));
+    assert!(vox.models[0].is_voxel_at_pos(128, 2, 1));
+
+    assert!(vox.models[0].mirror_copy(create_vox::Axis::X).is_err());
+    assert_eq!((256, 3, 3), vox.models[0].size);
+    assert_eq!(4, vox.models[0].num_of_voxels());
+}
+
+#[test]
+fn unknown_chunk_is_a_warning() {
+    let path = std::env::temp_dir().join("unknown_chunk_is_a_warning.vox");
+    let mut vox = VoxFile::new(5, 5, 5);
+    vox.models[0].add_voxel_at_pos(1, 2, 3, 4).unwrap();
+    vox.save(path.to_str().unwrap()).unwrap();
+
+    //add a chunk that is not known to the end of MAIN
+    let mut contents = std::fs::read(&path).unwrap();
+    contents.extend_from_slice(b"ABCD");
+    contents.extend_from_slice(&4u32.to_le_bytes());
+    contents.extend_from_slice(&0u32.to_le_bytes());
+    contents.extend_from_slice(&[1, 2, 3, 4]);
+    let main_size = (contents.len() - 20) as u32;
+    contents[16..20].copy_from_slice(&main_size.to_le_bytes());
+    std::fs::write(&path, contents).unwrap();
+
+    let (loaded, warnings) = VoxFile::open_with_warnings(path.to_str().unwrap()).unwrap();
+    assert_eq!(
+        vec![create_vox::Warning::UnknownChunk(String::from("ABCD"))],
+        warnings
+    );
+    assert!(loaded.models[0].is_voxel_at_pos(1, 2, 3));
+}
+
+#[test]
+fn model_name_round_trip() {
+    let path = std::env::temp_dir().join("model_name_round_trip.vox");
+    let mut vox = VoxFile::new(5, 5, 5);
+    let mut rock = Model::new(3, 3, 3);
+    rock.add_voxel_at_pos(1, 1, 1, 2).unwrap();
+    rock.set_name("rock");
+    vox.add_model(rock);
+    vox.models[0].set_name("tree");
+    vox.save(path.to_str().unwrap()).unwrap();
+
+    let loaded = VoxFile::load(path.to_str().unwrap());
+    let rock = loaded.model_by_name("rock").unwrap();
+    assert_eq!((3, 3, 3), rock.size);
+    assert!(rock.is_voxel_at_pos(1, 1, 1));
+    assert!(loaded.model_by_name("tree").is_some());
+}
+
+#[test]
+fn occupancy_map_matches_is_voxel_at_pos() {
+    let mut vox = VoxFile::new(8, 8, 8);
+    vox.models[0].add_cube(1, 2, 3, 5, 6, 7, 1).unwrap();
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 2).unwrap();
+    //the same position twice
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 3).unwrap();
+
+    let occupancy = vox.models[0].occupancy_map();
+    for x in 0..8 {
+        for y in 0..8 {
+            for z in 0..8 {
+                assert_eq!(
+                    vox.models[0].is_voxel_at_pos(x, y, z),
+                    occupancy.contains((x, y, z))
+                );
+            }
+        }
+    }
+    let index = occupancy.index_of((0, 0, 0)).unwrap();
+    assert_eq!(3, vox.models[0].voxels[index].color_index);
+}
+
+#[test]
+fn add_voxel_rgba_allocates_one_index() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    let teal = Color::new(0, 128, 128, 255);
+
+    vox.add_voxel_rgba(0, 1, 1, 1, teal).unwrap();
+    vox.add_voxel_rgba(0, 2, 2, 2, teal).unwrap();
+
+    //the color goes on the highest index that is not used
+    assert_eq!(255, vox.models[0].voxels[1].color_index);
+    assert_eq!(255, vox.models[0].voxels[2].color_index);
+    assert_eq!(teal, vox.get_palette_color(255));
+    assert_eq!(2, vox.palette_usage()[255]);
+    assert!(vox.add_voxel_rgba(0, 10, 0, 0, teal).is_err());
+}
+
+#[test]
+fn add_voxel_rgba_errors_leave_the_palette_alone() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].set_strict(true);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    let palette = vox.palette;
+    let teal = Color::new(0, 128, 128, 255);
+
+    assert!(matches!(
+        vox.add_voxel_rgba(1, 0, 0, 0, teal),
+        Err(create_vox::VoxError::InvalidModelIndex(1))
+    ));
+    assert!(matches!(
+        vox.add_voxel_rgba(0, 10, 0, 0, teal),
+        Err(create_vox::VoxError::VoxelOutOfBounds { .. })
+    ));
+    assert!(matches!(
+        vox.add_voxel_rgba(0, 1, 1, 1, teal),
+        Err(create_vox::VoxError::DuplicatePosition((1, 1, 1)))
+    ));
+    assert_eq!(palette, vox.palette);
+}
+
+#[test]
+fn add_voxel_rgba_full_palette() {
+    let mut vox = VoxFile::new(255, 1, 1);
+    for x in 0..255 {
+        vox.models[0].add_voxel_at_pos(x, 0, 0, x + 1).unwrap();
+        vox.set_palette_color(x + 1, x, 0, 0, 255);
+    }
+
+    let result = vox.add_voxel_rgba(0, 0, 0, 0, Color::new(0, 0, 255, 255));
+    assert!(matches!(result, Err(create_vox::VoxError::PaletteFull)));
+    vox.add_voxel_rgba(0, 0, 0, 0, Color::new(7, 0, 0, 255))
+        .unwrap();
+}
+
+#[test]
+fn sphere_volume() {
+    let mut vox = VoxFile::new(12, 12, 12);
+    vox.models[0].add_sphere((6, 6, 6), 5, 1, false).unwrap();
+
+    let expected = 4.0 / 3.0 * std::f32::consts::PI * 5.0f32.powi(3);
+    let count = vox.models[0].num_of_voxels() as f32;
+    assert!((count - expected).abs() / expected < 0.1);
+}
+
+#[test]
+fn sphere_is_clipped_at_zero() {
+    let mut vox = VoxFile::new(12, 12, 12);
+    vox.models[0].add_sphere((1, 1, 1), 4, 1, true).unwrap();
+
+    assert!(vox.models[0].is_voxel_at_pos(5, 1, 1));
+    assert!(vox.models[0].is_voxel_at_pos(1, 1, 5));
+    assert!(!vox.models[0].is_voxel_at_pos(1, 1, 1));
+}
+
+#[test]
+fn gradient_skips_index_zero() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    let untouched = vox.palette;
+    let red = Color::new(255, 0, 0, 255);
+    let blue = Color::new(0, 0, 255, 255);
+
+    vox.set_gradient(0, 5, red, blue);
+    assert_eq!(red, vox.get_palette_color(1));
+    assert_eq!(blue, vox.get_palette_color(5));
+    for index in 2..=4 {
+        assert_ne!(untouched[index as usize - 1], vox.get_palette_color(index));
+    }
+    assert_eq!(untouched[5..], vox.palette[5..]);
+
+    //index 0 is skipped instead of panicking
+    vox.add_gradient(0, 3, blue, red);
+    assert_ne!(red, vox.get_palette_color(1));
+    //the color of index 0 is kept in the last slot
+    assert_eq!(untouched[255], vox.palette[255]);
+
+    assert!(matches!(
+        vox.set_palette_gradient(0, 5, red, blue),
+        Err(create_vox::VoxError::InvalidColorIndex(0))
+    ));
+    assert_eq!(untouched[255], vox.palette[255]);
+}
+
+#[test]
+fn add_cube_checks_every_axis() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    assert!(vox.models[0].add_cube(0, 0, 0, 11, 5, 5, 1).is_err());
+    assert!(vox.models[0].add_cube(0, 0, 0, 5, 11, 5, 1).is_err());
+    assert!(vox.models[0].add_cube(0, 0, 0, 5, 5, 11, 1).is_err());
+    assert!(vox.models[0].is_empty());
+
+    vox.models[0].add_cube(0, 0, 0, 10, 10, 10, 1).unwrap();
+    assert_eq!(1000, vox.models[0].num_of_voxels());
+}
+
+#[test]
+fn add_cube_reversed_range() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    assert!(vox.models[0].add_cube(5, 0, 0, 2, 5, 5, 1).is_err());
+    assert!(vox.models[0].add_cube(0, 5, 0, 5, 2, 5, 1).is_err());
+    assert!(vox.models[0].add_cube(0, 0, 5, 5, 5, 2, 1).is_err());
+    assert!(vox.models[0].is_empty());
+}
+
+#[test]
+fn errors_can_be_matched() {
+    use create_vox::VoxError;
+
+    let mut vox = VoxFile::new(10, 10, 10);
+    let error = vox.models[0].add_voxel_at_pos(255, 1, 1, 1).unwrap_err();
+    assert!(matches!(
+        error,
+        VoxError::VoxelOutOfBounds {
+            pos: (255, 1, 1),
+            size: (10, 10, 10)
+        }
+    ));
+    assert_eq!(
+        "voxel position (255, 1, 1) is outside of the model size (10, 10, 10)",
+        error.to_string()
+    );
+
+    let error = vox.models[0].add_cube(0, 6, 0, 5, 2, 5, 1).unwrap_err();
+    assert!(matches!(error, VoxError::InvalidRange { start: 6, end: 2 }));
+
+    let error = vox.models[0]
+        .add_voxel(Voxel::new(1, 1, 10, 1))
+        .unwrap_err();
+    assert!(matches!(error, VoxError::VoxelOutOfBounds { .. }));
+}
+
+#[test]
+fn convex_hull_fills_slices() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    //a triangle on z 2
+    vox.models[0].add_voxel_at_pos(0, 0, 2, 1).unwrap();
+    vox.models[0].add_voxel_at_pos(6, 0, 2, 1).unwrap();
+    vox.models[0].add_voxel_at_pos(0, 6, 2, 1).unwrap();
+    //a line on z 4
+    vox.models[0].add_voxel_at_pos(1, 1, 4, 1).unwrap();
+    vox.models[0].add_voxel_at_pos(4, 4, 4, 1).unwrap();
+
+    assert!(matches!(
+        vox.models[0].fill_convex_hull(0),
+        Err(create_vox::VoxError::InvalidColorIndex(0))
+    ));
+    assert_eq!(5, vox.models[0].num_of_voxels());
+
+    vox.models[0].fill_convex_hull(3).unwrap();
+    assert!(vox.models[0].is_voxel_at_pos(3, 3, 2));
+    assert!(!vox.models[0].is_voxel_at_pos(4, 4, 2));
+    assert!(vox.models[0].is_voxel_at_pos(2, 2, 4));
+    assert!(!vox.models[0].is_voxel_at_pos(2, 1, 4));
+    //7 + 6 + ... + 1 voxels in the triangle and 4 in the line
+    assert_eq!(28 + 4, vox.models[0].num_of_voxels());
+}
+
+#[test]
+fn materials_round_trip() {
+    use create_vox::{Material, MaterialType};
+
+    let path = std::env::temp_dir().join("materials_round_trip.vox");
+    let mut vox = VoxFile::new(5, 5, 5);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    let mut glass = Material::new(1);
+    glass.material_type = Some(MaterialType::Glass);
+    glass.rough = Some(0.1);
+    glass
+        .other_properties
+        .push((String::from("_ior"), String::from("0.3")));
+    vox.materials.push(glass);
+    let mut metal = Material::new(2);
+    metal.material_type = Some(MaterialType::Metal);
+    metal.metal = Some(1.0);
+    vox.materials.push(metal);
+    vox.save(&path).unwrap();
+
+    let loaded = VoxFile::open(&path).unwrap();
+    assert_eq!(2, loaded.materials.len());
+    assert_eq!(vox.materials, loaded.materials);
+    assert_eq!(Some(MaterialType::Glass), loaded.materials[0].material_type);
+}
+
+#[test]
+fn rotation_round_trip() {
+    let path = std::env::temp_dir().join("rotation_round_trip.vox");
+    let mut vox = VoxFile::new(5, 5, 5);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    //90 degrees around z
+    vox.models[0].rotation = Some(17);
+    vox.add_model_copy(0, 10, 0, 0);
+    vox.copies[0].rotation = Some(40);
+    vox.save(&path).unwrap();
+
+    let mut loaded = VoxFile::open(&path).unwrap();
+    assert_eq!(Some(17), loaded.models[0].rotation);
+    assert_eq!(Some(40), loaded.copies[0].rotation);
+
+    loaded.save(&path).unwrap();
+    let reloaded = VoxFile::open(&path).unwrap();
+    assert_eq!(Some(17), reloaded.models[0].rotation);
+    assert_eq!(Some(40), reloaded.copies[0].rotation);
+}
+
+#[test]
+fn voxel_index_lookups() {
+    let mut model = Model::new(60, 60, 60);
+    model.add_cube(0, 0, 0, 60, 60, 60, 1).unwrap();
+    model.build_index();
+
+    //216,000 lookups over 216,000 voxels would take far too long if each lookup scanned
+    let mut found = 0;
+    for x in 0..60 {
+        for y in 0..60 {
+            for z in 0..60 {
+                if model.get_voxel_at(x, y, z).is_some() {
+                    found += 1;
+                }
+            }
+        }
+    }
+    assert_eq!(216_000, found);
+
+    model.retain_voxels(|voxel| voxel.position.0 < 30);
+    assert!(model.get_voxel_at(29, 5, 5).is_some());
+    assert!(model.get_voxel_at(30, 5, 5).is_none());
+
+    model.clear_voxels();
+    assert!(model.get_voxel_at(0, 0, 0).is_none());
+    model.add_voxel_at_pos(1, 2, 3, 4).unwrap();
+    assert_eq!(4, model.get_voxel_at(1, 2, 3).unwrap().color_index);
+
+    model.translate_to((5, 5, 5)).unwrap();
+    assert!(model.get_voxel_at(1, 2, 3).is_none());
+    assert!(model.get_voxel_at(5, 5, 5).is_some());
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn palette_from_image_is_exact_under_256_colors() {
+    let path = std::env::temp_dir().join("create_vox_palette_small.png");
+    let mut image = image::RgbaImage::new(16, 16);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        //the same 4 colors repeated should only use 4 indexes
+        *pixel = image::Rgba([(x % 2) as u8 * 200, (y % 2) as u8 * 100, 30, 255]);
+    }
+    image.save(&path).unwrap();
+
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.palette_from_image(&path).unwrap();
+    assert_eq!(Color::new(0, 0, 30, 255), vox.get_palette_color(1));
+    assert_eq!(Color::new(200, 0, 30, 255), vox.get_palette_color(2));
+    assert_eq!(Color::new(0, 100, 30, 255), vox.get_palette_color(3));
+    assert_eq!(Color::new(200, 100, 30, 255), vox.get_palette_color(4));
+    assert_eq!(Color::default_palette()[4], vox.get_palette_color(5));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn palette_from_image_reduces_large_images() {
+    let path = std::env::temp_dir().join("create_vox_palette_large.png");
+    let mut image = image::RgbaImage::new(64, 64);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        *pixel = image::Rgba([x as u8 * 4, y as u8 * 4, 0, 255]);
+    }
+    image.save(&path).unwrap();
+
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.palette_from_image(&path).unwrap();
+    let mut colors: Vec<_> = vox.palette[..255]
+        .iter()
+        .map(|color| (color.r, color.g, color.b))
+        .collect();
+    colors.sort_unstable();
+    colors.dedup();
+    assert_eq!(255, colors.len());
+}
+
+#[test]
+fn same_color_region_stops_at_other_colors() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_cube(0, 0, 0, 3, 3, 3, 1).unwrap();
+    //a wall of a different color between two blobs of the same color
+    model.add_cube(3, 0, 0, 4, 3, 3, 2).unwrap();
+    model.add_cube(4, 0, 0, 6, 3, 3, 1).unwrap();
+
+    let region = model.same_color_region((1, 1, 1));
+    assert_eq!(27, region.len());
+    assert!(region.iter().all(|pos| pos.0 < 3));
+    assert_eq!(18, model.same_color_region((5, 0, 0)).len());
+    assert_eq!(9, model.same_color_region((3, 2, 2)).len());
+    assert!(model.same_color_region((9, 9, 9)).is_empty());
+}
+
+#[test]
+fn obj_culls_hidden_faces() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_cube(0, 0, 0, 2, 2, 2, 1).unwrap();
+    let obj = vox.models[0].to_obj(&vox.palette);
+    assert_eq!(
+        12,
+        obj.lines().filter(|line| line.starts_with("f ")).count()
+    );
+
+    //the face between two colors is hidden but the sides can no longer be joined
+    vox.models[0].clear_voxels();
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    vox.models[0].add_voxel_at_pos(1, 0, 0, 2).unwrap();
+    let obj = vox.models[0].to_obj(&vox.palette);
+    assert_eq!(
+        20,
+        obj.lines().filter(|line| line.starts_with("f ")).count()
+    );
+    assert_eq!(
+        12,
+        obj.lines().filter(|line| line.starts_with("v ")).count()
+    );
+    assert!(obj.contains("usemtl color_1") && obj.contains("usemtl color_2"));
+
+    let mtl = vox.models[0].to_obj_mtl(&vox.palette);
+    assert_eq!(2, mtl.matches("newmtl").count());
+}
+
+#[test]
+fn set_palette_clears_the_rest() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    let colors: Vec<Color> = (1..=5).map(|i| Color::new(i * 40, 0, 0, 255)).collect();
+    vox.set_palette(&colors, create_vox::PalettePolicy::ClearRest);
+    for index in 1..=5 {
+        assert_eq!(colors[index as usize - 1], vox.get_palette_color(index));
+    }
+    for index in 6..=255 {
+        assert_eq!(0, vox.get_palette_color(index).a);
+    }
+
+    vox.set_palette_color(10, 1, 2, 3, 255);
+    vox.set_palette(&colors[..2], create_vox::PalettePolicy::LeaveRest);
+    assert_eq!(colors[2], vox.get_palette_color(3));
+    assert_eq!(Color::new(1, 2, 3, 255), vox.get_palette_color(10));
+}
+
+#[test]
+fn ascii_slice_of_square() {
+    let mut model = Model::new(5, 5, 5);
+    model.add_cube(1, 1, 2, 4, 4, 3, 1).unwrap();
+    assert_eq!(
+        "     \n ### \n ### \n ### \n     \n",
+        model.ascii_slice(create_vox::Axis::Z, 2)
+    );
+    assert_eq!(
+        "     \n     \n ### \n     \n     \n",
+        model.ascii_slice(create_vox::Axis::X, 2)
+    );
+    assert_eq!(25 + 5, model.ascii_slice(create_vox::Axis::Z, 0).len());
+}
+
+#[test]
+fn layers_round_trip() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    let ground = vox.add_layer(String::from("ground"), false);
+    let trees = vox.add_layer(String::from("trees"), true);
+    let sky = vox.add_layer(String::from("sky"), false);
+    assert_eq!((0, 1, 2), (ground, trees, sky));
+    vox.models[0].layer = Some(trees);
+
+    let path = std::env::temp_dir().join("create_vox_layers.vox");
+    vox.save(&path).unwrap();
+    let mut loaded = VoxFile::open(&path).unwrap();
+    loaded.save(&path).unwrap();
+    let loaded = VoxFile::open(&path).unwrap();
+
+    let layers: Vec<_> = loaded
+        .layers
+        .iter()
+        .map(|layer| {
+            (
+                layer.id(),
+                layer.name.clone().unwrap(),
+                layer.hidden.unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (0, String::from("ground"), false),
+            (1, String::from("trees"), true),
+            (2, String::from("sky"), false),
+        ],
+        layers
+    );
+    assert_eq!(Some(trees), loaded.models[0].layer);
+}
+
+#[test]
+fn non_vox_files_are_rejected() {
+    //the start of a png file
+    let png = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0, 0, 0, 13];
+    assert!(matches!(
+        VoxFile::from_bytes(&png),
+        Err(create_vox::VoxError::NotAVoxFile)
+    ));
+    assert!(matches!(
+        VoxFile::from_bytes(b""),
+        Err(create_vox::VoxError::NotAVoxFile)
+    ));
+
+    let path = std::env::temp_dir().join("create_vox_not_a_vox.txt");
+    std::fs::write(&path, "just some text, not voxels").unwrap();
+    assert!(matches!(
+        VoxFile::open(&path),
+        Err(create_vox::VoxError::NotAVoxFile)
+    ));
+}
+
+#[test]
+fn cube_gradient_ends_use_the_colors() {
+    let mut model = Model::new(10, 10, 10);
+    model
+        .add_cube_gradient((2, 0, 1), (6, 3, 3), create_vox::Axis::X, 50, 10)
+        .unwrap();
+    assert_eq!(5 * 4 * 3, model.num_of_voxels());
+    for voxel in model.voxels.iter() {
+        match voxel.position.0 {
+            2 => assert_eq!(50, voxel.color_index),
+            4 => assert_eq!(30, voxel.color_index),
+            6 => assert_eq!(10, voxel.color_index),
+            _ => assert!(voxel.color_index < 50 && voxel.color_index > 10),
+        }
+    }
+
+    assert!(matches!(
+        model.add_cube_gradient((0, 0, 0), (1, 1, 1), create_vox::Axis::Y, 0, 5),
+        Err(create_vox::VoxError::InvalidColorIndex(0))
+    ));
+}
+
+#[test]
+fn palette_gradient_black_to_white() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    let black = Color::new(0, 0, 0, 255);
+    let white = Color::new(255, 255, 255, 255);
+    vox.set_palette_gradient(1, 255, black, white).unwrap();
+
+    assert_eq!(black, vox.get_palette_color(1));
+    assert_eq!(white, vox.get_palette_color(255));
+    let middle = vox.get_palette_color(128);
+    assert!((126..=129).contains(&middle.r));
+    assert_eq!((middle.r, middle.r), (middle.g, middle.b));
+
+    vox.set_palette_gradient(40, 40, white, black).unwrap();
+    assert_eq!(white, vox.get_palette_color(40));
+    assert!(vox.set_palette_gradient(0, 10, black, white).is_err());
+}
+
+#[test]
+fn color_by_position_makes_stripes() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_cube(0, 0, 0, 10, 10, 2, 5).unwrap();
+    model.color_by_position(|x, _, _| if x % 2 == 0 { 1 } else { 2 });
+    for voxel in model.voxels.iter() {
+        let expected = if voxel.position.0 % 2 == 0 { 1 } else { 2 };
+        assert_eq!(expected, voxel.color_index);
+    }
+    assert_eq!(200, model.num_of_voxels());
+
+    model.color_by_position(|_, _, z| z);
+    assert_eq!(100, model.num_of_voxels());
+    assert!(model.voxels.iter().all(|voxel| voxel.color_index == 1));
+}
+
+#[test]
+fn line_along_the_diagonal() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_line((0, 0, 0), (9, 9, 9), 1).unwrap();
+    assert_eq!(10, model.num_of_voxels());
+    for i in 0..10 {
+        assert!(model.is_voxel_at_pos(i, i, i));
+    }
+
+    //a line with a steeper axis still has one voxel on every step of the longest axis
+    model.clear_voxels();
+    model.add_line((9, 0, 2), (0, 4, 7), 1).unwrap();
+    let mut xs: Vec<u8> = model.voxels.iter().map(|voxel| voxel.position.0).collect();
+    xs.sort_unstable();
+    assert_eq!((0..10).collect::<Vec<u8>>(), xs);
+
+    model.clear_voxels();
+    assert!(model.add_line((0, 0, 0), (3, 10, 0), 1).is_err());
+    assert!(matches!(
+        model.add_line((0, 0, 0), (3, 3, 0), 0),
+        Err(create_vox::VoxError::InvalidColorIndex(0))
+    ));
+    assert!(model.is_empty());
+}
+
+#[test]
+fn fill_empty_from_outside_leaves_cavity() {
+    let mut model = Model::new(9, 9, 9);
+    //a closed box with a 3x3x3 cavity inside
+    model.add_cube_shell((2, 2, 2), (6, 6, 6), 1).unwrap();
+
+    let filled = model.fill_empty_from((0, 0, 0), 2);
+    assert_eq!(9 * 9 * 9 - 5 * 5 * 5, filled);
+    for x in 3..6 {
+        for y in 3..6 {
+            for z in 3..6 {
+                assert!(!model.is_voxel_at_pos(x, y, z));
+            }
+        }
+    }
+    assert_eq!(0, model.fill_empty_from((0, 0, 0), 2));
+    assert_eq!(27, model.fill_empty_from((4, 4, 4), 3));
+}
+
+#[test]
+fn mirror_places_pair_on_other_side() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_voxel_at_pos(1, 4, 5, 3).unwrap();
+    model.mirror(create_vox::Axis::X);
+    assert_eq!(2, model.num_of_voxels());
+    assert_eq!(3, model.get_voxel_at(8, 4, 5).unwrap().color_index);
+
+    //mirroring again only finds voxels that are already there
+    model.mirror(create_vox::Axis::X);
+    assert_eq!(2, model.num_of_voxels());
+
+    model.mirror(create_vox::Axis::Z);
+    assert!(model.is_voxel_at_pos(1, 4, 4) && model.is_voxel_at_pos(8, 4, 4));
+    assert_eq!(4, model.num_of_voxels());
+}
+
+#[test]
+fn rotate90_four_times_is_the_same() {
+    let mut model = Model::new(5, 3, 2);
+    //an L shape
+    model.add_cube(0, 0, 0, 5, 1, 1, 1).unwrap();
+    model.add_cube(0, 1, 0, 1, 3, 1, 2).unwrap();
+    model.add_voxel_at_pos(4, 2, 1, 3).unwrap();
+    let positions = |model: &Model| {
+        let mut positions: Vec<_> = model
+            .voxels
+            .iter()
+            .map(|voxel| (voxel.position, voxel.color_index))
+            .collect();
+        positions.sort_unstable();
+        positions
+    };
+    let original = positions(&model);
+
+    for axis in [
+        create_vox::Axis::X,
+        create_vox::Axis::Y,
+        create_vox::Axis::Z,
+    ]
+    .iter()
+    {
+        model.rotate90(*axis, 1);
+        assert_ne!(original, positions(&model));
+        model.rotate90(*axis, 3);
+        assert_eq!((5, 3, 2), model.size);
+        assert_eq!(original, positions(&model));
+    }
+
+    model.rotate90(create_vox::Axis::Y, 1);
+    assert_eq!((2, 3, 5), model.size);
+    assert!(model
+        .voxels
+        .iter()
+        .all(|voxel| voxel.position.0 < 2 && voxel.position.1 < 3 && voxel.position.2 < 5));
+    for _ in 0..3 {
+        model.rotate90(create_vox::Axis::Y, 1);
+    }
+    assert_eq!(original, positions(&model));
+}
+
+#[test]
+fn resample_down_and_up() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_cube(0, 0, 0, 10, 10, 10, 1).unwrap();
+    let smaller = model.resample((5, 5, 5));
+    assert_eq!((5, 5, 5), smaller.size);
+    assert_eq!(125, smaller.num_of_voxels());
+
+    //half of the model keeps about half of the voxels, rounded to whole layers
+    model.retain_voxels(|voxel| voxel.position.2 < 5);
+    let smaller = model.resample((5, 5, 5));
+    assert!((50..=75).contains(&smaller.num_of_voxels()));
+
+    let larger = model.resample((20, 20, 20));
+    assert_eq!(4000, larger.num_of_voxels());
+}
+
+#[test]
+fn merge_layer_makes_one_model() {
+    let mut vox = VoxFile::new(4, 4, 4);
+    vox.models[0].add_cube(0, 0, 0, 4, 4, 1, 1).unwrap();
+    vox.models[0].layer = Some(1);
+    vox.models[0].position = Some((0, 0, 0));
+
+    let mut roof = Model::new(4, 4, 4);
+    roof.add_cube(0, 0, 3, 4, 4, 4, 2).unwrap();
+    roof.layer = Some(1);
+    roof.position = Some((0, 0, 2));
+    vox.add_model(roof);
+
+    let mut other = Model::new(3, 3, 3);
+    other.add_voxel_at_pos(0, 0, 0, 3).unwrap();
+    other.layer = Some(2);
+    vox.add_model(other);
+
+    vox.merge_layer(1).unwrap();
+    assert_eq!(2, vox.models.len());
+    assert_eq!((4, 4, 6), vox.models[0].size);
+    assert_eq!(32, vox.models[0].num_of_voxels());
+    assert_eq!(1, vox.models[0].get_voxel_at(0, 0, 0).unwrap().color_index);
+    assert_eq!(2, vox.models[0].get_voxel_at(0, 0, 5).unwrap().color_index);
+    assert_eq!(Some(2), vox.models[1].layer);
+
+    let mut far = Model::new(10, 10, 10);
+    far.position = Some((300, 0, 0));
+    far.layer = Some(2);
+    vox.add_model(far);
+    assert!(matches!(
+        vox.merge_layer(2),
+        Err(create_vox::VoxError::SizeTooLarge { .. })
+    ));
+    assert_eq!(3, vox.models.len());
+
+    let path = std::env::temp_dir().join("create_vox_merge_layer.vox");
+    vox.save(&path).unwrap();
+    assert_eq!(3, VoxFile::open(&path).unwrap().models.len());
+}
+
+#[test]
+fn merge_layer_keeps_copies() {
+    let mut vox = VoxFile::new(2, 2, 2);
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    vox.models[0].layer = Some(1);
+    vox.models[0].position = Some((0, 0, 0));
+
+    let mut other = Model::new(2, 2, 2);
+    other.add_voxel_at_pos(1, 1, 1, 2).unwrap();
+    other.layer = Some(2);
+    vox.add_model(other);
+
+    //a copy of the model on layer 2 that is on layer 1 and a copy of the model on layer 1 that is on layer 2
+    vox.add_model_copy(1, 4, 0, 0);
+    vox.copies[0].layer = Some(1);
+    vox.add_model_copy(0, 10, 0, 0);
+    vox.copies[1].layer = Some(2);
+
+    vox.merge_layer(1).unwrap();
+    assert_eq!(3, vox.models.len());
+    assert_eq!(0, vox.copies.len());
+    assert_eq!((6, 2, 2), vox.models[0].size);
+    assert_eq!(1, vox.models[0].get_voxel_at(0, 0, 0).unwrap().color_index);
+    assert_eq!(2, vox.models[0].get_voxel_at(5, 1, 1).unwrap().color_index);
+    assert_eq!(Some(2), vox.models[2].layer);
+    assert_eq!(Some((10, 0, 0)), vox.models[2].position);
+    assert_eq!(1, vox.models[2].get_voxel_at(0, 0, 0).unwrap().color_index);
+
+    vox.models[1].rotation = Some(17);
+    assert!(matches!(
+        vox.merge_layer(2),
+        Err(create_vox::VoxError::RotatedModel(17))
+    ));
+    assert_eq!(3, vox.models.len());
+}
+
+#[test]
+fn translate_moves_cube() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_cube(0, 0, 0, 3, 3, 3, 1).unwrap();
+    model.translate(2, 2, 2).unwrap();
+    assert_eq!(27, model.num_of_voxels());
+    assert!(model.is_voxel_at_pos(2, 2, 2));
+    assert!(model.is_voxel_at_pos(4, 4, 4));
+    assert!(!model.is_voxel_at_pos(1, 1, 1));
+}
+
+#[test]
+fn translate_below_zero_moves_nothing() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_voxel_at_pos(5, 5, 5, 1).unwrap();
+    model.add_voxel_at_pos(1, 5, 5, 1).unwrap();
+    assert!(matches!(
+        model.translate(-2, 0, 0),
+        Err(create_vox::VoxError::VoxelOutOfBounds { .. })
+    ));
+    assert!(model.is_voxel_at_pos(5, 5, 5));
+    assert!(model.is_voxel_at_pos(1, 5, 5));
+    assert!(model.translate(0, 0, 5).is_err());
+}
+
+#[test]
+fn exposed_faces_of_two_colors() {
+    let mut model = Model::new(10, 10, 10);
+    //a 2x2x2 cube with the bottom half one color and the top half another
+    model.add_cube(0, 0, 0, 2, 2, 1, 1).unwrap();
+    model.add_cube(0, 0, 1, 2, 2, 2, 2).unwrap();
+
+    let faces = model.exposed_faces_by_color();
+    assert_eq!(12, faces[1]);
+    assert_eq!(12, faces[2]);
+    assert_eq!(24, faces.iter().sum::<u64>());
+
+    model.add_voxel_at_pos(5, 5, 5, 1).unwrap();
+    assert_eq!(18, model.exposed_faces_by_color()[1]);
+}
+
+#[test]
+fn merge_overlapping_cubes() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_cube(0, 0, 0, 3, 3, 3, 1).unwrap();
+    let mut other = Model::new(3, 3, 3);
+    other.add_cube(0, 0, 0, 3, 3, 3, 2).unwrap();
+
+    //one layer of 9 voxels overlaps
+    model.merge(&other, (2, 0, 0)).unwrap();
+    assert_eq!(27 + 27 - 9, model.num_of_voxels());
+    assert_eq!(1, model.get_voxel_at(2, 1, 1).unwrap().color_index);
+    assert_eq!(2, model.get_voxel_at(4, 1, 1).unwrap().color_index);
+    assert_eq!(27, other.num_of_voxels());
+
+    assert!(model.merge(&other, (8, 0, 0)).is_err());
+    assert_eq!(45, model.num_of_voxels());
+}
+
+#[test]
+fn strict_model_rejects_duplicates() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    model.add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    assert_eq!(2, model.num_of_voxels());
+
+    model.clear_voxels();
+    model.set_strict(true);
+    model.add_voxel_at_pos(1, 2, 3, 1).unwrap();
+    assert!(matches!(
+        model.add_voxel_at_pos(1, 2, 3, 4),
+        Err(create_vox::VoxError::DuplicatePosition((1, 2, 3)))
+    ));
+    assert!(matches!(
+        model.add_voxel(Voxel::new(1, 2, 3, 5)),
+        Err(create_vox::VoxError::DuplicatePosition(_))
+    ));
+    model.build_index();
+    assert!(model.add_voxel_at_pos(1, 2, 3, 4).is_err());
+    model.add_voxel_at_pos(1, 2, 4, 4).unwrap();
+    assert_eq!(2, model.num_of_voxels());
+
+    //shapes are not checked, so they can be drawn over other voxels
+    model.add_cube(0, 0, 0, 3, 3, 5, 2).unwrap();
+    assert_eq!(2 + 45, model.num_of_voxels());
+}
+
+#[test]
+fn surface_voxels_of_solid_cube() {
+    let mut model = Model::new(6, 6, 6);
+    model.add_cube(1, 1, 1, 5, 5, 5, 1).unwrap();
+    let surface = model.surface_voxels();
+    assert_eq!(56, surface.len());
+    assert!(surface.iter().all(|voxel| {
+        let (x, y, z) = voxel.position;
+        [x, y, z].iter().any(|value| *value == 1 || *value == 4)
+    }));
+
+    //taking out a voxel in the middle of a side shows the one behind it
+    model.retain_voxels(|voxel| voxel.position != (2, 2, 1));
+    assert_eq!(56, model.surface_voxels().len());
+}
+
+#[test]
+fn bounding_cylinder_of_cylinder() {
+    let mut model = Model::new(40, 40, 40);
+    let radius = 12.0f32;
+    for x in 0..40u8 {
+        for y in 0..40u8 {
+            let distance = (x as f32 - 20.0).hypot(y as f32 - 20.0);
+            if distance <= radius {
+                model.fill_column(x, y, 5, 14, 1).unwrap();
+            }
+        }
+    }
+
+    let (found, height) = model.bounding_cylinder(create_vox::Axis::Z).unwrap();
+    assert!((found - radius).abs() < 0.5, "radius was {}", found);
+    assert_eq!(10, height);
+    assert!(Model::new(2, 2, 2)
+        .bounding_cylinder(create_vox::Axis::X)
+        .is_none());
+}
+
+#[test]
+fn model_iterators() {
+    let mut model = Model::new(10, 10, 10);
+    model.add_cube(0, 0, 0, 3, 1, 1, 1).unwrap();
+
+    let mut count = 0;
+    for voxel in &model {
+        assert_eq!(1, voxel.color_index);
+        count += 1;
+    }
+    assert_eq!(3, count);
+
+    for voxel in model.iter_mut() {
+        voxel.position.1 += 1;
+    }
+    let positions: Vec<_> = model.iter().map(|voxel| voxel.position).collect();
+    assert_eq!(vec![(0, 1, 0), (1, 1, 0), (2, 1, 0)], positions);
+}
+
+#[test]
+fn vxm_export_header_and_voxels() {
+    let mut vox = VoxFile::new(4, 3, 2);
+    vox.set_palette_color(7, 10, 20, 30, 255);
+    vox.models[0].add_cube(0, 0, 0, 4, 3, 1, 7).unwrap();
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 9).unwrap();
+    let path = std::env::temp_dir().join("create_vox_export.vxm");
+    vox.models[0].export_vxm(&vox.palette, &path).unwrap();
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(b"VXM4", &bytes[0..4]);
+    let read_u32 =
+        |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+    //z of the model is the y of the file
+    assert_eq!((4, 2, 3), (read_u32(4), read_u32(8), read_u32(12)));
+    let materials = bytes[28] as usize;
+    assert_eq!(2, materials);
+    assert_eq!(&[30, 20, 10, 255, 0], &bytes[29..34]);
+
+    let mut cells = 0;
+    let mut filled = 0;
+    let mut at = 29 + materials * 5;
+    while bytes[at] != 0 {
+        let (length, material) = (bytes[at] as usize, bytes[at + 1]);
+        cells += length;
+        if material != 255 {
+            filled += length;
+        }
+        at += 2;
+    }
+    assert_eq!(at + 1, bytes.len());
+    assert_eq!(4 * 3 * 2, cells);
+    assert_eq!(13, filled);
+
+    let missing = std::env::temp_dir()
+        .join("create_vox_missing_folder")
+        .join("export.vxm");
+    assert!(matches!(
+        vox.models[0].export_vxm(&vox.palette, &missing),
+        Err(create_vox::VoxError::Io(_))
+    ));
+}
+
+#[test]
+fn nearest_color_index_finds_closest_palette_color() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    let colors = [
+        Color::new(0, 255, 0, 255),
+        Color::new(255, 0, 0, 255),
+        Color::new(0, 0, 255, 255),
+    ];
+    vox.set_palette(&colors, create_vox::PalettePolicy::ClearRest);
+
+    assert_eq!(2, vox.nearest_color_index(Color::new(230, 20, 15, 255)));
+    assert_eq!(3, vox.nearest_color_index(Color::new(10, 30, 200, 0)));
+    //index 0 is never given even though the color there is closer
+    vox.palette[255] = Color::new(230, 20, 15, 255);
+    assert_eq!(2, vox.nearest_color_index(Color::new(230, 20, 15, 255)));
+}
+
+#[test]
+fn content_hash_ignores_order_but_not_color() {
+    let mut first = Model::new(10, 10, 10);
+    let mut second = Model::new(10, 10, 10);
+    let voxels = [(1, 2, 3, 4), (5, 5, 5, 1), (0, 0, 9, 200)];
+    for &(x, y, z, index) in voxels.iter() {
+        first.add_voxel_at_pos(x, y, z, index).unwrap();
+    }
+    for &(x, y, z, index) in voxels.iter().rev() {
+        second.add_voxel_at_pos(x, y, z, index).unwrap();
+    }
+    assert_eq!(first.content_hash(), second.content_hash());
+
+    second.voxels[0].color_index = 5;
+    assert_ne!(first.content_hash(), second.content_hash());
+
+    //same voxels in a model of a different size
+    let mut bigger = Model::new(11, 10, 10);
+    for &(x, y, z, index) in voxels.iter() {
+        bigger.add_voxel_at_pos(x, y, z, index).unwrap();
+    }
+    assert_ne!(first.content_hash(), bigger.content_hash());
+
+    //the hash does not change between builds
+    let mut small = Model::new(1, 1, 1);
+    small.add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    assert_eq!(0x9392_4a9e_baa4_52df, small.content_hash());
+}
+
+#[test]
+fn default_palette_matches_magicavoxel() {
+    let vox = VoxFile::new(10, 10, 10);
+    assert_eq!(Color::new(255, 255, 255, 255), vox.get_palette_color(1));
+    assert_eq!(Color::new(255, 0, 0, 255), vox.get_palette_color(36));
+    assert_eq!(Color::new(102, 153, 255, 255), vox.get_palette_color(121));
+    assert_eq!(Color::new(0, 0, 51, 255), vox.get_palette_color(215));
+    assert_eq!(Color::new(238, 0, 0, 255), vox.get_palette_color(216));
+    assert_eq!(Color::new(34, 34, 34, 255), vox.get_palette_color(254));
+    assert_eq!(Color::new(17, 17, 17, 255), vox.get_palette_color(255));
+    assert_eq!(Color::new(0, 0, 0, 0), vox.palette[255]);
+}
+
+#[test]
+fn remap_color_range_moves_section() {
+    let mut model = Model::new(10, 10, 10);
+    for (x, index) in [10, 11, 12, 13, 9].iter().enumerate() {
+        model.add_voxel_at_pos(x as u8, 0, 0, *index).unwrap();
+    }
+    model.remap_color_range(10, 12, 100).unwrap();
+    let indexes: Vec<u8> = model.iter().map(|voxel| voxel.color_index).collect();
+    assert_eq!(vec![100, 101, 102, 13, 9], indexes);
+
+    assert!(matches!(
+        model.remap_color_range(5, 4, 1),
+        Err(create_vox::VoxError::InvalidRange { start: 5, end: 4 })
+    ));
+    assert!(matches!(
+        model.remap_color_range(0, 4, 1),
+        Err(create_vox::VoxError::InvalidColorIndex(0))
+    ));
+    assert!(matches!(
+        model.remap_color_range(100, 102, 254),
+        Err(create_vox::VoxError::InvalidColorIndex(254))
+    ));
+    //nothing is changed by an error
+    assert_eq!(100, model.voxels[0].color_index);
+}
+
+#[test]
+fn hollow_keeps_shell_of_solid_cube() {
+    let mut vox = VoxFile::new(20, 20, 20);
+    vox.models[0].add_cube(3, 3, 3, 8, 8, 8, 1).unwrap();
+    vox.models[0].build_index();
+    vox.models[0].hollow();
+
+    assert_eq!(98, vox.models[0].num_of_voxels());
+    assert!(vox.models[0].is_voxel_at_pos(3, 5, 5));
+    assert!(!vox.models[0].is_voxel_at_pos(4, 5, 5));
+    assert!(!vox.models[0].is_voxel_at_pos(5, 5, 5));
+
+    //a shell has nothing more to remove
+    vox.models[0].hollow();
+    assert_eq!(98, vox.models[0].num_of_voxels());
+}
+
+#[test]
+fn split_by_layer_keeps_copies_on_other_layers() {
+    let mut vox = VoxFile::new(3, 3, 3);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 2).unwrap();
+    vox.models[0].layer = Some(vox.add_layer(String::from("models"), false));
+    let copies = vox.add_layer(String::from("copies"), false);
+    vox.add_model_copy(0, 10, 0, 0);
+    vox.copies[0].layer = Some(copies);
+
+    let files = vox.split_by_layer();
+    assert_eq!(2, files.len());
+    assert_eq!(1, files[0].models.len());
+    assert_eq!(0, files[0].copies.len());
+    assert_eq!(1, files[1].models.len());
+    assert_eq!(0, files[1].copies.len());
+    assert_eq!(Some((10, 0, 0)), files[1].models[0].position);
+    assert_eq!(Some(copies), files[1].models[0].layer);
+    assert_eq!(
+        2,
+        files[1].models[0]
+            .get_voxel_at(1, 1, 1)
+            .unwrap()
+            .color_index
+    );
+}
+
+#[test]
+fn split_by_layer_makes_file_per_layer() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.set_palette_color(4, 1, 2, 3, 255);
+    let ground = vox.add_layer(String::from("ground"), false);
+    let trees = vox.add_layer(String::from("trees"), false);
+    vox.models[0].layer = Some(ground);
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 4).unwrap();
+    for size in [3, 4].iter() {
+        let mut tree = Model::new(*size, *size, *size);
+        tree.layer = Some(trees);
+        vox.add_model(tree);
+    }
+    vox.add_model_copy(2, 20, 0, 0);
+    vox.copies[0].layer = Some(trees);
+    vox.add_model_copy(2, 40, 0, 0);
+
+    let mut files = vox.split_by_layer();
+    assert_eq!(2, files.len());
+    assert_eq!(1, files[0].models[0].num_of_voxels());
+    assert_eq!(0, files[0].copies.len());
+    assert_eq!(2, files[1].models.len());
+    assert_eq!((3, 3, 3), files[1].models[0].size);
+    assert_eq!((4, 4, 4), files[1].models[1].size);
+    assert_eq!(Some(String::from("trees")), files[1].layers[0].name);
+    //the second copy is on layer 0, which does not have the model, so it is made into a model there
+    assert_eq!(2, files[0].models.len());
+    assert_eq!((4, 4, 4), files[0].models[1].size);
+    assert_eq!(Some((40, 0, 0)), files[0].models[1].position);
+    assert_eq!(1, files[1].copies.len());
+    assert_eq!(1, files[1].copies[0].model_id);
+
+    for (index, file) in files.iter_mut().enumerate() {
+        assert_eq!(Color::new(1, 2, 3, 255), file.get_palette_color(4));
+        let path = std::env::temp_dir().join(format!("create_vox_split_{}.vox", index));
+        file.save(&path).unwrap();
+        let read = VoxFile::open(&path).unwrap();
+        assert_eq!(file.models.len(), read.models.len());
+    }
+}
+
+#[test]
+fn open_save_round_trip_keeps_models() {
+    let mut vox = VoxFile::new(8, 8, 8);
+    vox.models[0].add_cube(0, 0, 0, 4, 4, 4, 5).unwrap();
+    vox.models[0].layer = Some(vox.add_layer(String::from("base"), false));
+    let mut second = Model::new(3, 6, 9);
+    second.add_voxel_at_pos(2, 5, 8, 200).unwrap();
+    second.position = Some((20, -4, 7));
+    vox.add_model(second);
+
+    let first_path = std::env::temp_dir().join("create_vox_round_trip_1.vox");
+    let second_path = std::env::temp_dir().join("create_vox_round_trip_2.vox");
+    vox.save(&first_path).unwrap();
+    let mut opened = VoxFile::open(&first_path).unwrap();
+    opened.save(&second_path).unwrap();
+    let reopened = VoxFile::open(&second_path).unwrap();
+
+    for file in [&opened, &reopened].iter() {
+        assert_eq!(vox.models.len(), file.models.len());
+        for (model, read) in vox.models.iter().zip(file.models.iter()) {
+            assert_eq!(model.content_hash(), read.content_hash());
+        }
+        assert_eq!(Some((20, -4, 7)), file.models[1].position);
+        assert_eq!(Some(0), file.models[0].layer);
+        assert_eq!(vox.palette[..], file.palette[..]);
+    }
+}
+
+#[test]
+fn open_broken_files_returns_errors() {
+    let path = std::env::temp_dir().join("create_vox_broken.vox");
+    let mut vox = VoxFile::new(4, 4, 4);
+    vox.models[0].add_cube(0, 0, 0, 4, 4, 4, 1).unwrap();
+    vox.save(&path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+
+    //cut off in the middle of the voxels, and after the chunk header of the size
+    for length in [12, 40, 60, bytes.len() - 1].iter() {
+        assert!(matches!(
+            VoxFile::from_bytes(&bytes[..*length]),
+            Err(create_vox::VoxError::InvalidFile(_))
+        ));
+    }
+
+    std::fs::write(&path, &bytes[..50]).unwrap();
+    assert!(matches!(
+        VoxFile::open(&path),
+        Err(create_vox::VoxError::InvalidFile(_))
+    ));
+
+    //a model frame that is not a number
+    vox.add_palette_cycle_animation(0, 2, (1, 2)).unwrap();
+    vox.save(&path).unwrap();
+    let mut bytes = std::fs::read(&path).unwrap();
+    let frame = bytes
+        .windows(6)
+        .position(|window| window == b"_f\x01\x00\x00\x00")
+        .unwrap();
+    bytes[frame + 6] = b'x';
+    assert!(matches!(
+        VoxFile::from_bytes(&bytes),
+        Err(create_vox::VoxError::InvalidFile(_))
+    ));
+
+    let mut vox = VoxFile::new(4, 4, 4);
+    vox.models[0].add_cube(0, 0, 0, 4, 4, 4, 1).unwrap();
+    vox.models[0].position = Some((1, 2, 3));
+    vox.add_layer(String::from("layer"), false);
+    vox.save(&path).unwrap();
+    let saved = std::fs::read(&path).unwrap();
+    let find = |pattern: &[u8]| {
+        saved
+            .windows(pattern.len())
+            .position(|window| window == pattern)
+            .unwrap()
+    };
+    let frames = i32::MAX.to_le_bytes();
+    let changes: Vec<(usize, &[u8])> = vec![
+        //a voxel with color index 0
+        (find(b"XYZI") + 19, &[0]),
+        //a translation with a part that is not a number, and one with only 2 numbers
+        (find(b"1 2 3"), b"1 x 3"),
+        (find(b"1 2 3"), b"12 34"),
+        //more frames than fit in the chunk, the root transform has no attributes before the count
+        (find(b"nTRN") + 32, &frames),
+        //a hidden flag that is not a number
+        (find(b"_hidden\x01\x00\x00\x00") + 11, b"x"),
+    ];
+    for (at, replacement) in changes {
+        let mut bytes = saved.clone();
+        bytes[at..at + replacement.len()].copy_from_slice(replacement);
+        assert!(matches!(
+            VoxFile::from_bytes(&bytes),
+            Err(create_vox::VoxError::InvalidFile(_))
+        ));
+    }
+}
+
+#[test]
+fn open_file_without_palette_or_nodes() {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"VOX ");
+    bytes.extend_from_slice(&150u32.to_le_bytes());
+    bytes.extend_from_slice(b"MAIN");
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&(24u32 + 20).to_le_bytes());
+    bytes.extend_from_slice(b"SIZE");
+    for value in [12u32, 0, 2, 3, 4].iter() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(b"XYZI");
+    for value in [8u32, 0, 1].iter() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[1, 2, 3, 7]);
+
+    let vox = VoxFile::from_bytes(&bytes).unwrap();
+    assert_eq!((2, 3, 4), vox.models[0].size);
+    assert_eq!(7, vox.models[0].get_voxel_at(1, 2, 3).unwrap().color_index);
+    assert_eq!(Color::default_palette()[..], vox.palette[..]);
+}
+
+#[test]
+fn is_oversized_flags_huge_models() {
+    let mut model = Model::new(256, 256, 256);
+    //set directly since adding this many voxels one at a time is slow
+    model.voxels = vec![Voxel::new(0, 0, 0, 1); 1 << 22];
+    assert!(!model.is_oversized());
+    model.voxels.push(Voxel::new(1, 0, 0, 1));
+    assert!(model.is_oversized());
+}
+
+#[test]
+fn bounding_box_gives_inclusive_corners() {
+    let mut model = Model::new(20, 20, 20);
+    assert_eq!(None, model.bounding_box());
+    model.add_voxel_at_pos(2, 8, 6, 1).unwrap();
+    model.add_voxel_at_pos(7, 3, 9, 1).unwrap();
+    model.add_voxel_at_pos(4, 5, 4, 1).unwrap();
+    assert_eq!(Some(((2, 3, 4), (7, 8, 9))), model.bounding_box());
+
+    model.auto_size();
+    assert_eq!((6, 6, 6), model.size);
+    assert_eq!(Some(((0, 0, 0), (5, 5, 5))), model.bounding_box());
+}
+
+#[test]
+fn touches_face_checks_each_side() {
+    let mut model = Model::new(8, 6, 4);
+    model.add_voxel_at_pos(0, 2, 1, 1).unwrap();
+    model.add_voxel_at_pos(3, 5, 2, 1).unwrap();
+
+    assert!(model.touches_face(create_vox::Axis::X, false));
+    assert!(!model.touches_face(create_vox::Axis::X, true));
+    assert!(!model.touches_face(create_vox::Axis::Y, false));
+    assert!(model.touches_face(create_vox::Axis::Y, true));
+    assert!(!model.touches_face(create_vox::Axis::Z, false));
+    assert!(!model.touches_face(create_vox::Axis::Z, true));
+
+    model.add_voxel_at_pos(7, 0, 3, 1).unwrap();
+    assert!(model.touches_face(create_vox::Axis::X, true));
+    assert!(model.touches_face(create_vox::Axis::Z, true));
+}
+
+#[test]
+fn color_histogram_counts_each_index() {
+    let mut model = Model::new(10, 10, 10);
+    for (x, index) in [6, 6, 5, 7].iter().enumerate() {
+        model.add_voxel_at_pos(x as u8, 0, 0, *index).unwrap();
+    }
+
+    let histogram = model.color_histogram();
+    assert_eq!(2, histogram[6]);
+    assert_eq!(1, histogram[5]);
+    assert_eq!(1, histogram[7]);
+    assert_eq!(4, histogram.iter().sum::<u32>());
+    assert_eq!(vec![5, 6, 7], model.used_color_indices());
+    assert!(Model::new(2, 2, 2).used_color_indices().is_empty());
+}
+
+#[test]
+fn from_voxels_sizes_to_fit() {
+    let voxels = vec![
+        Voxel::new(3, 1, 2, 1),
+        Voxel::new(9, 0, 4, 2),
+        Voxel::new(5, 4, 6, 3),
+    ];
+    let model = Model::from_voxels(voxels.clone());
+    assert_eq!((10, 5, 7), model.size);
+    assert_eq!(3, model.num_of_voxels());
+    assert_eq!(2, model.get_voxel_at(9, 0, 4).unwrap().color_index);
+
+    let moved = Model::from_voxels_origin(voxels);
+    assert_eq!((7, 5, 5), moved.size);
+    assert_eq!(1, moved.get_voxel_at(0, 1, 0).unwrap().color_index);
+    assert_eq!(3, moved.get_voxel_at(2, 4, 4).unwrap().color_index);
+
+    assert_eq!((1, 1, 1), Model::from_voxels(Vec::new()).size);
+}
+
+#[test]
+fn save_with_buffer_size_writes_same_file() {
+    let mut vox = VoxFile::new(64, 64, 64);
+    vox.models[0].add_cube(0, 0, 0, 64, 64, 32, 9).unwrap();
+    vox.models[0]
+        .add_sphere((32, 32, 40), 20, 3, false)
+        .unwrap();
+
+    let default_path = std::env::temp_dir().join("create_vox_buffer_default.vox");
+    let small_path = std::env::temp_dir().join("create_vox_buffer_small.vox");
+    let large_path = std::env::temp_dir().join("create_vox_buffer_large.vox");
+    vox.save(&default_path).unwrap();
+    vox.save_with_buffer_size(&small_path, 16).unwrap();
+    vox.save_with_buffer_size(&large_path, 4 * 1024 * 1024)
+        .unwrap();
+
+    let expected = std::fs::read(&default_path).unwrap();
+    assert_eq!(expected, std::fs::read(&small_path).unwrap());
+    assert_eq!(expected, std::fs::read(&large_path).unwrap());
+    let read = VoxFile::open(&large_path).unwrap();
+    assert_eq!(vox.models[0].content_hash(), read.models[0].content_hash());
+}
+
+#[test]
+fn grid_matches_voxels_of_loaded_model() {
+    let mut vox = VoxFile::new(12, 12, 12);
+    vox.models[0].add_sphere((6, 6, 6), 5, 4, true).unwrap();
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 9).unwrap();
+    let path = std::env::temp_dir().join("create_vox_grid.vox");
+    vox.save(&path).unwrap();
+
+    let model = &VoxFile::open(&path).unwrap().models[0];
+    let grid = model.grid();
+    assert_eq!(model.size, grid.size());
+    for x in 0..12 {
+        for y in 0..12 {
+            for z in 0..12 {
+                assert_eq!(model.is_voxel_at_pos(x, y, z), grid.get(x, y, z).is_some());
+            }
+        }
+    }
+    assert_eq!(Some(9), grid.get(0, 0, 0));
+    assert_eq!(Some(4), grid.get(6, 6, 1));
+}
+
+#[test]
+fn reset_clears_everything_but_size() {
+    let mut vox = VoxFile::new(4, 5, 6);
+    vox.add_model(Model::new(2, 2, 2));
+    let model = &mut vox.models[1];
+    model.add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    model.set_name("thing");
+    model.position = Some((4, 5, 6));
+    model.rotation = Some(17);
+    model.layer = Some(2);
+    model.set_strict(true);
+
+    model.reset();
+    assert_eq!((2, 2, 2), model.size);
+    assert!(model.is_empty());
+    assert_eq!(None, model.name);
+    assert_eq!(None, model.position);
+    assert_eq!(None, model.rotation);
+    assert_eq!(None, model.layer);
+    assert_eq!(1, model.get_id());
+    //the model is still strict
+    model.add_voxel_at_pos(1, 1, 1, 3).unwrap();
+    assert!(matches!(
+        model.add_voxel_at_pos(1, 1, 1, 4),
+        Err(create_vox::VoxError::DuplicatePosition((1, 1, 1)))
+    ));
+}
+
+#[test]
+fn assign_all_to_layer_moves_models_and_copies() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.models[0].layer = Some(4);
+    vox.add_model(Model::new(5, 5, 5));
+    vox.add_model_copy(1, 10, 0, 0);
+
+    vox.assign_all_to_layer(2);
+    assert!(vox.models.iter().all(|model| model.layer == Some(2)));
+    assert_eq!(Some(2), vox.copies[0].layer);
+
+    let path = std::env::temp_dir().join("create_vox_assign_layer.vox");
+    vox.save(&path).unwrap();
+    let read = VoxFile::open(&path).unwrap();
+    assert!(read.models.iter().all(|model| model.layer == Some(2)));
+}
+
+#[test]
+fn hex_colors_parse_and_round_trip() {
+    let orange = Color::new(255, 136, 0, 255);
+    assert_eq!(orange, Color::from_hex("#ff8800").unwrap());
+    assert_eq!(orange, Color::from_hex("ff8800ff").unwrap());
+    assert_eq!(orange, Color::from_hex("#FF8800").unwrap());
+    assert_eq!(Color::new(1, 2, 3, 4), Color::from_hex("01020304").unwrap());
+
+    for hex in [
+        "#xyz", "", "#", "ff880", "#ff8800f", "##ff8800", "ff88zz", "+f+f+f",
+    ]
+    .iter()
+    {
+        assert!(matches!(
+            Color::from_hex(hex),
+            Err(create_vox::VoxError::InvalidHexColor(_))
+        ));
+    }
+
+    let color = Color::new(18, 52, 86, 120);
+    assert_eq!("#12345678", color.to_hex());
+    assert_eq!(color, Color::from_hex(&color.to_hex()).unwrap());
+}
+
+#[test]
+fn color_index_range_gives_min_and_max() {
+    let mut model = Model::new(10, 10, 10);
+    assert_eq!(None, model.color_index_range());
+    for (x, index) in [50, 3, 200, 120].iter().enumerate() {
+        model.add_voxel_at_pos(x as u8, 0, 0, *index).unwrap();
+    }
+    assert_eq!(Some((3, 200)), model.color_index_range());
+}
+
+#[test]
+fn rgba_chunk_keeps_palette_indexes() {
+    let mut vox = VoxFile::new(4, 4, 4);
+    vox.set_palette_color(1, 255, 0, 0, 255);
+    vox.set_palette_color(2, 0, 255, 0, 255);
+    vox.set_palette_color(255, 0, 0, 255, 255);
+    vox.models[0].add_voxel_at_pos(0, 0, 0, 1).unwrap();
+    let path = std::env::temp_dir().join("create_vox_rgba.vox");
+    vox.save(&path).unwrap();
+
+    //the first color in the chunk is for index 1, there is no color for index 0
+    let bytes = std::fs::read(&path).unwrap();
+    let chunk = bytes.windows(4).position(|name| name == b"RGBA").unwrap();
+    assert_eq!(
+        1024,
+        u32::from_le_bytes([
+            bytes[chunk + 4],
+            bytes[chunk + 5],
+            bytes[chunk + 6],
+            bytes[chunk + 7]
+        ])
+    );
+    let colors = &bytes[chunk + 12..chunk + 12 + 1024];
+    assert_eq!(&[255, 0, 0, 255], &colors[0..4]);
+    assert_eq!(&[0, 255, 0, 255], &colors[4..8]);
+    assert_eq!(&[0, 0, 255, 255], &colors[254 * 4..255 * 4]);
+
+    let read = VoxFile::open(&path).unwrap();
+    assert_eq!(Color::new(255, 0, 0, 255), read.get_palette_color(1));
+    assert_eq!(Color::new(0, 255, 0, 255), read.get_palette_color(2));
+    assert_eq!(Color::new(0, 0, 255, 255), read.get_palette_color(255));
+    assert_eq!(vox.palette[..], read.palette[..]);
+}
+
+#[test]
+fn shift_hue_turns_palette_colors() {
+    let mut vox = VoxFile::new(10, 10, 10);
+    vox.set_palette_color(1, 255, 0, 0, 255);
+    vox.set_palette_color(2, 255, 136, 0, 200);
+    vox.set_palette_color(3, 90, 90, 90, 255);
+    vox.models[0].add_voxel_at_pos(1, 1, 1, 2).unwrap();
+
+    vox.shift_hue(180.0);
+    assert_eq!(Color::new(0, 255, 255, 255), vox.get_palette_color(1));
+    //a hue of 32 degrees becomes 212 degrees
+    assert_eq!(Color::new(0, 119, 255, 200), vox.get_palette_color(2));
+    //grey has no hue to turn
+    assert_eq!(Color::new(90, 90, 90, 255), vox.get_palette_color(3));
+    assert_eq!(2, vox.models[0].voxels[0].color_index);
+
+    vox.shift_hue(-180.0);
+    assert_eq!(Color::new(255, 0, 0, 255), vox.get_palette_color(1));
+    assert_eq!(Color::new(255, 136, 0, 200), vox.get_palette_color(2));
+}
+
+#[test]
+fn add_cylinder_stays_inside_model() {
+    let mut model = Model::new(7, 8, 7);
+    model
+        .add_cylinder((3, 0, 3), 3, 8, create_vox::Axis::Y, 2, false)
+        .unwrap();
+    assert!(model
+        .iter()
+        .all(|voxel| voxel.position.0 < 7 && voxel.position.1 < 8 && voxel.position.2 < 7));
+    //every layer along y is the same circle
+    let layer = model.iter().filter(|voxel| voxel.position.1 == 0).count();
+    assert_eq!(layer * 8, model.voxels.len());
+    assert!(model.is_voxel_at_pos(0, 4, 3));
+    assert!(!model.is_voxel_at_pos(0, 4, 0));
+
+    let mut hollow = Model::new(7, 8, 7);
+    hollow
+        .add_cylinder((3, 0, 3), 3, 8, create_vox::Axis::Y, 2, true)
+        .unwrap();
+    assert!(hollow.is_voxel_at_pos(6, 0, 3));
+    assert!(!hollow.is_voxel_at_pos(3, 0, 3));
+    assert!(hollow.voxels.len() < model.voxels.len());
+
+    //one too tall, and one too wide
+    let mut small = Model::new(7, 8, 7);
+    assert!(small
+        .add_cylinder((3, 1, 3), 3, 8, create_vox::Axis::Y, 2, false)
+        .is_err());
+    assert!(small
+        .add_cylinder((4, 0, 3), 3, 8, create_vox::Axis::Y, 2, false)
+        .is_err());
+    assert!(matches!(
+        small.add_cylinder((3, 0, 3), 3, 8, create_vox::Axis::Y, 0, false),
+        Err(create_vox::VoxError::InvalidColorIndex(0))
+    ));
+    assert!(small.is_empty());
+}
+
+#[test]
+fn add_noise_is_repeatable_for_a_seed() {
+    let positions = |model: &Model| {
+        let mut positions: Vec<_> = model.iter().map(|voxel| voxel.position).collect();
+        positions.sort_unstable();
+        positions
+    };
+
+    let mut first = Model::new(16, 16, 16);
+    let mut second = Model::new(16, 16, 16);
+    first.add_noise(0.3, 5, 1234).unwrap();
+    second.add_noise(0.3, 5, 1234).unwrap();
+    assert_eq!(positions(&first), positions(&second));
+    assert!(first.num_of_voxels() > 0 && first.num_of_voxels() < 16 * 16 * 16);
+
+    let mut other_seed = Model::new(16, 16, 16);
+    other_seed.add_noise(0.3, 5, 4321).unwrap();
+    assert_ne!(positions(&first), positions(&other_seed));
+
+    //filled positions are skipped
+    let mut full = Model::new(16, 16, 16);
+    full.add_voxel_at_pos(0, 0, 0, 9).unwrap();
+    full.add_noise(1.0, 5, 7).unwrap();
+    assert_eq!(16 * 16 * 16, full.num_of_voxels());
+    assert_eq!(9, full.get_voxel_at(0, 0, 0).unwrap().color_index);
+
+    let mut none = Model::new(16, 16, 16);
+    none.add_noise(0.0, 5, 7).unwrap();
+    assert!(matches!(
+        none.add_noise(1.0, 0, 7),
+        Err(create_vox::VoxError::InvalidColorIndex(0))
+    ));
+    assert!(none.is_empty());
+}
+
+#[test]
+fn shear_keeps_the_index_up_to_date() {
+    let mut model = Model::new(1, 1, 4);
+    model.fill_column(0, 0, 0, 3, 1).unwrap();
+    model.build_index();
+
+    let sheared = model
+        .shear(create_vox::Axis::Z, create_vox::Axis::X, 1.0)
+        .unwrap();
+    for z in 0..4 {
+        assert!(sheared.get_voxel_at(z, 0, z).is_some());
+    }
+    assert!(sheared.get_voxel_at(0, 0, 3).is_none());
+}
+
+#[test]
+fn shear_along_the_same_axis_is_an_error() {
+    let mut model = Model::new(4, 4, 4);
+    model.add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    assert!(matches!(
+        model.shear(create_vox::Axis::Y, create_vox::Axis::Y, 0.5),
+        Err(create_vox::VoxError::SameAxis(create_vox::Axis::Y))
+    ));
+}
+
+#[test]
+fn mirror_copy_skips_voxels_outside_of_the_size() {
+    let mut model = Model::new(4, 4, 4);
+    model.add_voxel_at_pos(1, 1, 1, 1).unwrap();
+    model.voxels.push(Voxel::new(200, 1, 1, 2));
+    model.mirror_copy(create_vox::Axis::X).unwrap();
+    assert_eq!(3, model.num_of_voxels());
+    assert!(model.is_voxel_at_pos(6, 1, 1));
+
+    let mut empty = Model::new(0, 4, 4);
+    empty.voxels.push(Voxel::new(0, 1, 1, 1));
+    empty.mirror_copy(create_vox::Axis::X).unwrap();
+    assert_eq!(0, empty.size.0);
+    assert_eq!(1, empty.num_of_voxels());
+}
+
+#[test]
+fn adding_color_zero_is_an_error() {
+    let mut model = Model::new(10, 10, 10);
+    let is_color_error = |result| matches!(result, Err(create_vox::VoxError::InvalidColorIndex(0)));
+    assert!(is_color_error(model.add_voxel_at_pos(1, 1, 1, 0)));
+    assert!(is_color_error(model.add_cube(0, 0, 0, 5, 5, 5, 0)));
+    assert!(is_color_error(model.add_box_frame((0, 0, 0), (5, 5, 5), 0)));
+    assert!(is_color_error(model.add_cube_shell(
+        (0, 0, 0),
+        (5, 5, 5),
+        0
+    )));
+    assert!(is_color_error(model.add_sphere((5, 5, 5), 3, 0, false)));
+    assert!(is_color_error(model.fill_column(1, 1, 0, 9, 0)));
+    assert_eq!(0, model.num_of_voxels());
+}
+
+#[test]
+fn merge_with_removes_voxels_resolved_to_zero() {
+    let mut model = Model::new(4, 4, 4);
+    model.build_index();
+    model.add_voxel_at_pos(0, 0, 0, 3).unwrap();
+    model.add_voxel_at_pos(1, 0, 0, 3).unwrap();
+    let mut other = Model::new(2, 1, 1);
+    other.add_voxel_at_pos(0, 0, 0, 5).unwrap();
+    other.add_voxel_at_pos(1, 0, 0, 7).unwrap();
+
+    model
+        .merge_with(
+            &other,
+            (1, 0, 0),
+            |_, added| if added == 5 { 0 } else { added },
+        )
+        .unwrap();
+    assert_eq!(2, model.num_of_voxels());
+    assert!(model.get_voxel_at(1, 0, 0).is_none());
+    assert_eq!(7, model.get_voxel_at(2, 0, 0).unwrap().color_index);
+    assert!(model.iter().all(|voxel| voxel.color_index != 0));
 }